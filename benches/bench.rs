@@ -1,8 +1,6 @@
 use glassbench::*;
 use std::path::PathBuf;
 
-#[path = "../src/shared/mod.rs"]
-mod wordstat;
 use wordstat::*;
 
 fn bench_examples(bencher: &mut Bench) {
@@ -16,17 +14,39 @@ fn bench_examples(bencher: &mut Bench) {
         bottom_words:    3,
         recursive:       true,
         follow_symlinks: false,
+        include:         Vec::new(),
+        exclude:         Vec::new(),
+        extensions:      Vec::new(),
+        no_ignore:       false,
+        hidden:          false,
         hide_empty:      false,
         show_all_words:  false,
         outfile:         None,
         emojis:          false,
+        ngrams:          0,
+        stopwords:       None,
+        encoding:        None,
+        lossy:           false,
+        binary:          false,
+        input_format:    None,
+        wpm:             false,
+        code_mode:       None,
+        strip_gutenberg: false,
+        tokenizer:       None,
+        stemmer:         None,
+        normalize:       None,
+        casefold:        false,
+        strip_accents:   false,
     };
     let pwd = std::fs::canonicalize(std::env::current_dir().unwrap_or_else(|_| PathBuf::new()))
         .unwrap_or_else(|_| PathBuf::new());
     bencher.task("Examples", move |task| {
         task.iter(|| {
             let (_, _) = analyze(
-                &paths.iter().map(|path| AnalyzeSource::Path(path.to_owned())).collect(),
+                &paths
+                    .iter()
+                    .map(|path| AnalyzeSource::Path(path.to_owned()))
+                    .collect::<Vec<_>>(),
                 &args,
                 &pwd,
                 |error| eprintln!("{}", error),
@@ -1,31 +1,41 @@
-#[path = "../shared/mod.rs"]
-mod shared;
-
 use std::{
     fs::{canonicalize, File},
-    io::{BufWriter, Write},
-    path::{Path, PathBuf},
+    io::{BufWriter, IsTerminal, Write},
+    path::PathBuf,
     sync::{Arc, Mutex},
     time::Duration,
 };
 
-use clap::{ErrorKind, IntoApp, Parser};
+use clap::{builder::PossibleValuesParser, ErrorKind, IntoApp, Parser, ValueEnum};
 use console::{style, Emoji};
 use indicatif::{ProgressBar, ProgressStyle};
 use pathdiff::diff_paths;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use regex::{Regex, RegexBuilder};
 
-use crate::shared::{analyze, Analysis, AnalyzeSource, Args};
+use wordstat::{
+    analyze, builtin_tokenizer, write_csv, write_json, write_tsv, Analysis, AnalyzeSource, Args,
+    CodeMode, Encoding, InputFormat, Normalization, RegexTokenizer, Stemmer, Stopwords,
+    UnicodeTokenizer, UniqueString, TOKENIZERS,
+};
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct CliArgs {
-    /// Path to one or multiple files or directories of files to analyze
+    /// Path to one or multiple files or directories of files to analyze, `-` to read from stdin
     path:            Vec<String>,
     /// Normalize casing by lowercasing each occuring word
     #[clap(short, long)]
     lowercase:       bool,
+    /// Normalize casing with full Unicode case folding, so that "Straße" matches "STRASSE"
+    #[clap(long)]
+    casefold:        bool,
+    /// Strip diacritics such as accents from each occuring word
+    #[clap(long)]
+    strip_accents:   bool,
+    /// Unicode normalization form that each occuring word is converted to
+    #[clap(long, value_parser = PossibleValuesParser::new(Normalization::NAMES))]
+    normalize:       Option<String>,
     /// Number of top words to show per file (0 = all)
     #[clap(short, long, default_value_t = 10)]
     top_words:       usize,
@@ -38,6 +48,48 @@ pub struct CliArgs {
     /// Print combined analysis with all words found in files
     #[clap(short, long)]
     show_all_words:  bool,
+    /// Count word n-grams of the given length within sentences (0 = disabled)
+    #[clap(short, long, default_value_t = 0)]
+    ngrams:          usize,
+    /// Exclude the bundled stopwords of a language from the word frequencies
+    #[clap(long, value_parser = PossibleValuesParser::new(Stopwords::LANGUAGES))]
+    stopwords_lang:  Vec<String>,
+    /// Exclude the stopwords listed in a file from the word frequencies
+    #[clap(long)]
+    stopwords:       Vec<PathBuf>,
+    /// Count words as their stems with the Snowball stemmer of a language
+    #[clap(long, value_parser = PossibleValuesParser::new(Stemmer::LANGUAGES))]
+    stem:            Option<String>,
+    /// Count words as the lemmas listed for them in a file, with a lemma and its forms per line
+    #[clap(long, conflicts_with = "stem")]
+    lemmas:          Option<PathBuf>,
+    /// Encoding of the files, detected from their content if not specified
+    #[clap(long, value_parser = parse_encoding)]
+    encoding:        Option<&'static Encoding>,
+    /// Replace malformed byte sequences instead of skipping the file
+    #[clap(long)]
+    lossy:           bool,
+    /// Analyze files with binary content instead of skipping them
+    #[clap(long)]
+    binary:          bool,
+    /// Format of the files, detected from their extension if not specified
+    #[clap(long, value_parser = PossibleValuesParser::new(InputFormat::NAMES))]
+    input_format:    Option<String>,
+    /// Only analyze the comments, string literals or identifiers of source files
+    #[clap(long, value_parser = PossibleValuesParser::new(CodeMode::NAMES))]
+    code_mode:       Option<String>,
+    /// Only analyze the text between the *** START OF and *** END OF markers of Gutenberg e-books
+    #[clap(long)]
+    strip_gutenberg: bool,
+    /// Tokenizer of words and sentences
+    #[clap(long, value_parser = PossibleValuesParser::new(TOKENIZERS), default_value = "unicode")]
+    tokenizer:       String,
+    /// Count the matches of a regex as words instead of using a tokenizer
+    #[clap(long, conflicts_with = "tokenizer")]
+    token_regex:     Option<String>,
+    /// Show the words per minute of subtitles calculated from their cue timings
+    #[clap(long)]
+    wpm:             bool,
     /// Filter printed words by string or regex
     #[clap(short, long)]
     word_filter:     Option<String>,
@@ -47,9 +99,43 @@ pub struct CliArgs {
     /// Follow symlinks
     #[clap(short, long)]
     follow_symlinks: bool,
+    /// Only analyze files in directories and archives matching a glob pattern
+    #[clap(long)]
+    include:         Vec<String>,
+    /// Skip files and directories in directories and archives matching a glob pattern
+    #[clap(long)]
+    exclude:         Vec<String>,
+    /// Only analyze files in directories and archives with an extension
+    #[clap(long)]
+    extension:       Vec<String>,
+    /// Don't skip files listed in .gitignore and .ignore files
+    #[clap(long)]
+    no_ignore:       bool,
+    /// Don't skip hidden files and directories
+    #[clap(long)]
+    hidden:          bool,
     /// The path to a file that the results will be written to, will overwrite if it already exists
     #[clap(short, long)]
     outfile:         Option<String>,
+    /// Format of the results, written to stdout instead of the report if no outfile is given
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+    format:          OutputFormat,
+}
+
+fn parse_encoding(label: &str) -> Result<&'static Encoding, String> {
+    Encoding::for_label(label.as_bytes()).ok_or_else(|| format!("unknown encoding `{}`", label))
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Words with their frequency in the combined analysis, one per line
+    Text,
+    /// All analyses and the combined analysis with statistics and word frequencies
+    Json,
+    /// Table of word frequencies of all analyses and the combined analysis
+    Csv,
+    /// Table of word frequencies of all analyses and the combined analysis, tab-separated
+    Tsv,
 }
 
 fn print_analysis(
@@ -59,6 +145,16 @@ fn print_analysis(
         eprintln!("{}{}", Emoji("⚠️ ", ""), style("No words in file").red());
         return (0, 0);
     }
+    if let Some(encoding) = analysis.encoding.filter(|encoding| encoding.name() != "UTF-8") {
+        println!("{}Encoding: {}", Emoji("🔤 ", ""), style(encoding.name()).blue().bright());
+    }
+    if analysis.replaced_bytes > 0 {
+        println!(
+            "{}Replaced bytes: {}",
+            Emoji("⚠️ ", ""),
+            style(&format!("{}", analysis.replaced_bytes)).yellow()
+        );
+    }
     println!(
         "{}Word count: {}",
         Emoji("🔢 ", ""),
@@ -84,6 +180,18 @@ fn print_analysis(
         Emoji("🔢 ", ""),
         style(&format!("{}", analysis.word_uniqs)).blue().bright()
     );
+    if analysis.stopword_count > 0 {
+        println!(
+            "{}Stopword count: {}",
+            Emoji("🔢 ", ""),
+            style(&format!("{}", analysis.stopword_count)).blue().bright()
+        );
+        println!(
+            "{}Unique stopwords: {}",
+            Emoji("🔢 ", ""),
+            style(&format!("{}", analysis.stopword_uniqs)).blue().bright()
+        );
+    }
     println!(
         "{}Word frequency mean: {}",
         Emoji("📊 ", ""),
@@ -104,32 +212,119 @@ fn print_analysis(
         Emoji("📊 ", ""),
         style(&format!("{:.1}", analysis.word_dist_mode)).blue().bright()
     );
+    for (name, score) in [
+        ("Flesch reading ease", analysis.flesch_reading_ease),
+        ("Flesch-Kincaid grade level", analysis.flesch_kincaid_grade),
+        ("Gunning fog index", analysis.gunning_fog),
+        ("SMOG grade", analysis.smog),
+        ("Coleman-Liau index", analysis.coleman_liau),
+        ("Automated readability index", analysis.automated_readability),
+    ] {
+        println!(
+            "{}{}: {}",
+            Emoji("📖 ", ""),
+            name,
+            style(&format!("{:.1}", score)).blue().bright()
+        );
+    }
+    if analysis.words_per_minute > 0.0 {
+        println!(
+            "{}Words per minute: {}",
+            Emoji("⏱️ ", ""),
+            style(&format!("{:.1}", analysis.words_per_minute)).blue().bright()
+        );
+    }
 
     let filtered_word_count = if let Some(regex) = regex {
         analysis.word_freq.par_iter().filter(|(_, word)| regex.is_match(word)).count()
     } else {
         analysis.word_freq.len()
     };
-    if filtered_word_count == 0 {
+    let printed = if filtered_word_count == 0 {
         eprintln!("{}{}", Emoji("⚠️ ", ""), style("No words in file matching filter").red());
-        return (0, 0);
+        0
     } else {
         println!(
             "{}Words matching filter: {}",
             Emoji("🔎 ", ""),
             style(&format!("{}", filtered_word_count)).blue().bright()
         );
+        print_frequencies(
+            &analysis.word_freq,
+            filtered_word_count,
+            "words",
+            Some(analysis),
+            top_words,
+            bottom_words,
+            emojis,
+            regex,
+        )
+    };
+
+    if !analysis.ngram_freq.is_empty() {
+        println!(
+            "{}N-gram count: {}",
+            Emoji("🔢 ", ""),
+            style(&format!("{}", analysis.ngram_count)).blue().bright()
+        );
+        let filtered_ngram_count = if let Some(regex) = regex {
+            analysis
+                .ngram_freq
+                .par_iter()
+                .filter(|(_, ngram)| regex.is_match(ngram))
+                .count()
+        } else {
+            analysis.ngram_freq.len()
+        };
+        if filtered_ngram_count > 0 {
+            println!(
+                "{}N-grams matching filter: {}",
+                Emoji("🔎 ", ""),
+                style(&format!("{}", filtered_ngram_count)).blue().bright()
+            );
+            print_frequencies(
+                &analysis.ngram_freq,
+                filtered_ngram_count,
+                "n-grams",
+                None,
+                top_words,
+                bottom_words,
+                false,
+                regex,
+            );
+        }
+    }
+
+    (printed, filtered_word_count)
+}
+
+/// Prints the surface forms that were counted as a stemmed word if they differ from it.
+fn print_forms(analysis: Option<&Analysis>, word: &UniqueString) {
+    let Some(forms) = analysis.map(|analysis| analysis.word_forms(word)) else {
+        return;
+    };
+    if forms.len() > 1 || forms.first().is_some_and(|form| form != word) {
+        let forms = forms.iter().map(UniqueString::as_str).collect::<Vec<_>>();
+        print!(" {}", style(format!("({})", forms.join(", "))).dim());
     }
+}
 
+#[allow(clippy::too_many_arguments)]
+fn print_frequencies(
+    frequencies: &[(usize, UniqueString)], filtered_count: usize, name: &str,
+    forms: Option<&Analysis>, top_words: usize, bottom_words: usize, emojis: bool,
+    regex: &Option<Regex>,
+) -> usize {
     println!(
-        "{}Top words{}",
+        "{}Top {}{}",
         Emoji("📈 ", ""),
+        name,
         if regex.is_some() { " (filtered):" } else { ":" }
     );
     let regex = regex.as_ref();
-    let pad = format!("{}", analysis.word_freq[0].0).len();
+    let pad = format!("{}", frequencies[0].0).len();
     let mut printed_top = 0;
-    for (freq, string) in analysis.word_freq.iter() {
+    for (freq, string) in frequencies.iter() {
         if top_words > 0 && printed_top >= top_words {
             break;
         };
@@ -140,6 +335,7 @@ fn print_analysis(
                 style(&format!("{:width$}", freq, width = pad)).bold().blue(),
                 style(string).green(),
             );
+            print_forms(forms, string);
             if emojis {
                 if let Some(e) = emojis::get_by_shortcode(&string.to_lowercase()) {
                     print!(" {}", e);
@@ -150,16 +346,16 @@ fn print_analysis(
     }
 
     let mut printed_bottom = 0;
-    if bottom_words > 0 && top_words != 0 && printed_top < filtered_word_count {
-        let pad =
-            format!("{}", analysis.word_freq.iter().nth_back(0).map(|n| n.0).unwrap_or(0)).len();
+    if bottom_words > 0 && top_words != 0 && printed_top < filtered_count {
+        let pad = format!("{}", frequencies.iter().nth_back(0).map(|n| n.0).unwrap_or(0)).len();
         println!(
-            "{}Bottom words{}",
+            "{}Bottom {}{}",
             Emoji("📉 ", ""),
+            name,
             if regex.is_some() { " (filtered):" } else { ":" }
         );
 
-        for (freq, string) in analysis.word_freq.iter().rev() {
+        for (freq, string) in frequencies.iter().rev() {
             if bottom_words > 0 && printed_bottom >= bottom_words {
                 break;
             };
@@ -170,6 +366,7 @@ fn print_analysis(
                     style(&format!("{:width$}", freq, width = pad)).bold().blue(),
                     style(string).green(),
                 );
+                print_forms(forms, string);
                 if emojis {
                     if let Some(e) = emojis::get_by_shortcode(&string.to_lowercase()) {
                         print!(" {}", e);
@@ -180,28 +377,31 @@ fn print_analysis(
         }
     }
 
-    (printed_top + printed_bottom, filtered_word_count)
+    printed_top + printed_bottom
 }
 
-fn print_analysis_file(analysis: &Analysis, path: &Path) {
-    let file = File::create(path);
-    if let Err(error) = file {
-        eprintln!(
-            "{}{} {}: {}",
-            Emoji("⚠️ ", ""),
-            style("Could not open output file").red(),
-            style(&path.display()).blue(),
-            style(&error).red()
-        );
-        return;
-    };
-    let file = file.unwrap();
-    let mut writer = BufWriter::new(file);
+fn print_analysis_file<W: Write>(mut writer: W, analysis: &Analysis) -> std::io::Result<()> {
     for (freq, string) in analysis.word_freq.iter() {
-        writeln!(writer, "{}: {}", string, freq).unwrap_or_else(|error| {
-            eprintln!("{}{}", Emoji("⚠️ ", ""), style(&error).red());
-        });
+        writeln!(writer, "{}: {}", string, freq)?;
     }
+    Ok(())
+}
+
+fn print_results<W: Write>(
+    writer: W, analyses: &[Analysis], total: Option<&Analysis>, format: OutputFormat,
+    regex: &Option<Regex>,
+) {
+    let result = match format {
+        OutputFormat::Text => {
+            total.map_or(Ok(()), |analysis| print_analysis_file(writer, analysis))
+        }
+        OutputFormat::Json => write_json(writer, analyses, total, regex.as_ref()),
+        OutputFormat::Csv => write_csv(writer, analyses, total, regex.as_ref()),
+        OutputFormat::Tsv => write_tsv(writer, analyses, total, regex.as_ref()),
+    };
+    result.unwrap_or_else(|error| {
+        eprintln!("{}{}", Emoji("⚠️ ", ""), style(&error).red());
+    });
 }
 
 fn main() {
@@ -213,23 +413,79 @@ fn main() {
     }))
     .unwrap_or_else(|error| app.lock().unwrap().error(ErrorKind::Io, format!("{}", error)).exit());
 
-    let mut paths = Vec::new();
+    let read_stdin = || {
+        std::io::read_to_string(std::io::stdin())
+            .map(AnalyzeSource::Content)
+            .unwrap_or_else(|error| {
+                app.lock()
+                    .unwrap()
+                    .error(ErrorKind::Io, format!("Could not read stdin: {}", error))
+                    .exit()
+            })
+    };
+
+    let mut sources = Vec::new();
     for path in &args.path {
-        paths.push(canonicalize(path).unwrap_or_else(|error| {
+        if path == "-" {
+            sources.push(read_stdin());
+            continue;
+        }
+        sources.push(AnalyzeSource::Path(canonicalize(path).unwrap_or_else(|error| {
             app.lock()
                 .unwrap()
                 .error(ErrorKind::Io, format!("Could not resolve {}: {}", path, error))
                 .exit()
-        }))
+        })))
+    }
+
+    if sources.is_empty() && !std::io::stdin().is_terminal() {
+        sources.push(read_stdin());
     }
 
-    if paths.is_empty() {
+    if sources.is_empty() {
         app.lock()
             .unwrap()
             .error(ErrorKind::InvalidValue, "No files or directories specified")
             .exit()
     }
 
+    let stopwords = if args.stopwords_lang.is_empty() && args.stopwords.is_empty() {
+        None
+    } else {
+        let mut stopwords = Stopwords::default();
+        for language in &args.stopwords_lang {
+            stopwords.extend(Stopwords::builtin(language).unwrap_or_default());
+        }
+        for path in &args.stopwords {
+            stopwords.extend(Stopwords::from_file(path).unwrap_or_else(|error| {
+                app.lock()
+                    .unwrap()
+                    .error(
+                        ErrorKind::Io,
+                        format!("Could not read stopwords {}: {}", path.display(), error),
+                    )
+                    .exit()
+            }));
+        }
+        Some(Arc::new(stopwords))
+    };
+
+    let stemmer = match (&args.stem, &args.lemmas) {
+        (Some(language), _) => Stemmer::snowball(language).map(Arc::new),
+        (None, Some(path)) => {
+            Some(Arc::new(Stemmer::lemmas_from_file(path).unwrap_or_else(|error| {
+                app.lock()
+                    .unwrap()
+                    .error(
+                        ErrorKind::Io,
+                        format!("Could not read lemmas {}: {}", path.display(), error),
+                    )
+                    .exit()
+            })))
+        }
+        (None, None) => None,
+    };
+
     let word_filter = args.word_filter.as_ref();
     let regex = match word_filter {
         None => None,
@@ -277,12 +533,27 @@ fn main() {
         }
     };
 
-    println!(
-        "{}checking {} {}",
-        Emoji("🔍 ", ""),
-        paths.len(),
-        if paths.len() > 1 { "paths" } else { "path" }
-    );
+    let tokenizer = match &args.token_regex {
+        Some(pattern) => Arc::new(RegexTokenizer::new(pattern).unwrap_or_else(|error| {
+            app.lock()
+                .unwrap()
+                .error(ErrorKind::InvalidValue, format!("Could not create token regex: {}", error))
+                .exit()
+        })),
+        None => builtin_tokenizer(&args.tokenizer).unwrap_or_else(|| Arc::new(UnicodeTokenizer)),
+    };
+
+    let format = args.format;
+    let print_report = format == OutputFormat::Text || args.outfile.is_some();
+
+    if print_report {
+        println!(
+            "{}checking {} {}",
+            Emoji("🔍 ", ""),
+            sources.len(),
+            if sources.len() > 1 { "paths" } else { "path" }
+        );
+    }
 
     let bar_progress =
         ProgressBar::new(0).with_style(
@@ -290,24 +561,43 @@ fn main() {
                 .template("{spinner:.green} {elapsed_precise} [{wide_bar:.green}] {pos}/{len}\n{spinner:.green} {wide_msg}")
                 .unwrap(),
         );
-    bar_progress.set_length(paths.len() as u64);
+    bar_progress.set_length(sources.len() as u64);
     bar_progress.set_position(0);
     bar_progress.enable_steady_tick(Duration::from_millis(12));
 
     let args = Args {
-        lowercase:       args.lowercase,
-        top_words:       args.top_words,
-        bottom_words:    args.bottom_words,
-        recursive:       args.recursive,
+        lowercase: args.lowercase,
+        top_words: args.top_words,
+        bottom_words: args.bottom_words,
+        recursive: args.recursive,
         follow_symlinks: args.follow_symlinks,
-        hide_empty:      false,
-        outfile:         args.outfile,
-        emojis:          args.emojis,
-        show_all_words:  args.show_all_words,
+        include: args.include,
+        exclude: args.exclude,
+        extensions: args.extension,
+        no_ignore: args.no_ignore,
+        hidden: args.hidden,
+        hide_empty: false,
+        outfile: args.outfile,
+        emojis: args.emojis,
+        show_all_words: args.show_all_words,
+        ngrams: args.ngrams,
+        stopwords,
+        encoding: args.encoding,
+        lossy: args.lossy,
+        binary: args.binary,
+        input_format: args.input_format.as_deref().and_then(InputFormat::from_name),
+        wpm: args.wpm,
+        code_mode: args.code_mode.as_deref().and_then(CodeMode::from_name),
+        strip_gutenberg: args.strip_gutenberg,
+        tokenizer: Some(tokenizer),
+        stemmer,
+        normalize: args.normalize.as_deref().and_then(Normalization::from_name),
+        casefold: args.casefold,
+        strip_accents: args.strip_accents,
     };
 
     let (mut analyses, total) = analyze(
-        &paths.iter().map(|path| AnalyzeSource::Path(path.to_owned())).collect(),
+        &sources,
         &args,
         &pwd,
         |error| eprintln!("{}{}", Emoji("⚠️ ", ""), style(&error).red()),
@@ -320,27 +610,37 @@ fn main() {
 
     bar_progress.finish_and_clear();
 
-    for analysis in analyses.iter() {
-        println!();
-        println!(
-            "{}File: {}",
-            Emoji("📁 ", ""),
-            style(
-                analysis
-                    .file
-                    .as_ref()
-                    .map(|file| diff_paths(file, &pwd)
-                        .unwrap_or_else(|| file.clone())
-                        .display()
-                        .to_string())
-                    .unwrap_or_else(|| "<none>".to_string())
-            )
-            .blue()
-        );
-        print_analysis(analysis, args.top_words, args.bottom_words, args.emojis, &regex);
+    if print_report {
+        for analysis in analyses.iter() {
+            println!();
+            println!(
+                "{}File: {}",
+                Emoji("📁 ", ""),
+                style(
+                    analysis
+                        .file
+                        .as_ref()
+                        .map(|file| diff_paths(file, &pwd)
+                            .unwrap_or_else(|| file.clone())
+                            .display()
+                            .to_string())
+                        .unwrap_or_else(|| "<stdin>".to_string())
+                )
+                .blue()
+            );
+            print_analysis(analysis, args.top_words, args.bottom_words, args.emojis, &regex);
+        }
     }
 
-    if let Some(analysis) = total {
+    if let Some(analysis) = total.as_ref().filter(|_| print_report) {
+        if analysis.skipped_binary > 0 {
+            println!();
+            println!(
+                "{}Skipped binary files: {}",
+                Emoji("⚠️ ", ""),
+                style(&format!("{}", analysis.skipped_binary)).yellow()
+            );
+        }
         let (printed_total, filtered_word_count) = if analyses_count > 1 {
             println!();
             println!(
@@ -350,7 +650,7 @@ fn main() {
                 style(&format!("{}", analyses_count)).bold().magenta(),
                 style("files").yellow()
             );
-            print_analysis(&analysis, args.top_words, args.bottom_words, args.emojis, &regex)
+            print_analysis(analysis, args.top_words, args.bottom_words, args.emojis, &regex)
         } else {
             (0, 0)
         };
@@ -363,19 +663,37 @@ fn main() {
                 style(&format!("{}", analyses_count)).bold().magenta(),
                 style("files (all words)").yellow()
             );
-            print_analysis(&analysis, 0, 0, args.emojis, &regex);
+            print_analysis(analysis, 0, 0, args.emojis, &regex);
         }
+    }
 
-        if let Some(path) = args.outfile {
-            println!();
-            let outfile = PathBuf::from(&path);
-            println!(
-                "{}Writing results to {}",
-                Emoji("🖥️ ", ""),
-                style(diff_paths(&outfile, &pwd).unwrap_or_else(|| outfile.clone()).display())
-                    .blue()
-            );
-            print_analysis_file(&analysis, &outfile);
+    if let Some(path) = args.outfile {
+        println!();
+        let outfile = PathBuf::from(&path);
+        println!(
+            "{}Writing results to {}",
+            Emoji("🖥️ ", ""),
+            style(diff_paths(&outfile, &pwd).unwrap_or_else(|| outfile.clone()).display()).blue()
+        );
+        match File::create(&outfile) {
+            Ok(file) => {
+                print_results(BufWriter::new(file), &analyses, total.as_ref(), format, &regex)
+            }
+            Err(error) => eprintln!(
+                "{}{} {}: {}",
+                Emoji("⚠️ ", ""),
+                style("Could not open output file").red(),
+                style(&outfile.display()).blue(),
+                style(&error).red()
+            ),
         }
+    } else if format != OutputFormat::Text {
+        print_results(
+            BufWriter::new(std::io::stdout().lock()),
+            &analyses,
+            total.as_ref(),
+            format,
+            &regex,
+        );
     }
 }
@@ -4,8 +4,6 @@ use native_windows_derive as nwd;
 use native_windows_gui as nwg;
 
 mod report;
-#[path = "../shared/mod.rs"]
-mod shared;
 
 use std::{
     cell::RefCell,
@@ -38,7 +36,7 @@ use winapi::{
 };
 
 use report::*;
-use shared::{analyze, Analysis, AnalyzeSource, Args};
+use wordstat::{analyze, Analysis, AnalyzeSource, Args, Stopwords};
 
 static ICON: &[u8] = include_bytes!("../../resources/book.ico");
 
@@ -138,6 +136,51 @@ pub struct App {
         OnMenuItemSelected: [App::menu_settings_all_words],
     )]
     menu_settings_all_words:  nwg::MenuItem,
+    #[nwg_control(
+        text: "Count word &bigrams",
+        parent: menu_settings,
+        check: false
+    )]
+    #[nwg_events(
+        OnMenuItemSelected: [App::menu_settings_bigrams],
+    )]
+    menu_settings_bigrams:    nwg::MenuItem,
+    #[nwg_control(
+        text: "Exclude English &stopwords",
+        parent: menu_settings,
+        check: false
+    )]
+    #[nwg_events(
+        OnMenuItemSelected: [App::menu_settings_stopwords],
+    )]
+    menu_settings_stopwords:  nwg::MenuItem,
+    #[nwg_control(
+        text: "Strip &Gutenberg boilerplate",
+        parent: menu_settings,
+        check: false
+    )]
+    #[nwg_events(
+        OnMenuItemSelected: [App::menu_settings_gutenberg],
+    )]
+    menu_settings_gutenberg:  nwg::MenuItem,
+    #[nwg_control(
+        text: "&Replace malformed bytes",
+        parent: menu_settings,
+        check: false
+    )]
+    #[nwg_events(
+        OnMenuItemSelected: [App::menu_settings_lossy],
+    )]
+    menu_settings_lossy:      nwg::MenuItem,
+    #[nwg_control(
+        text: "Show &words per minute of subtitles",
+        parent: menu_settings,
+        check: false
+    )]
+    #[nwg_events(
+        OnMenuItemSelected: [App::menu_settings_wpm],
+    )]
+    menu_settings_wpm:        nwg::MenuItem,
 
     #[nwg_layout(
         parent: window,
@@ -345,6 +388,11 @@ impl App {
         self.menu_settings_hide_empty.set_checked(args.hide_empty);
         self.menu_settings_all_words.set_checked(args.show_all_words);
         self.menu_settings_emojis.set_checked(args.emojis);
+        self.menu_settings_bigrams.set_checked(args.ngrams > 1);
+        self.menu_settings_stopwords.set_checked(args.stopwords.is_some());
+        self.menu_settings_gutenberg.set_checked(args.strip_gutenberg);
+        self.menu_settings_lossy.set_checked(args.lossy);
+        self.menu_settings_wpm.set_checked(args.wpm);
     }
 
     fn menu_settings_lowercase(&self) {
@@ -383,6 +431,54 @@ impl App {
         self.start_analyze(sources);
     }
 
+    fn menu_settings_bigrams(&self) {
+        {
+            let mut args = self.args.borrow_mut();
+            args.ngrams = if args.ngrams > 1 { 0 } else { 2 };
+        }
+        let sources = self.last_source.borrow().clone();
+        self.start_analyze(sources);
+    }
+
+    fn menu_settings_stopwords(&self) {
+        {
+            let mut args = self.args.borrow_mut();
+            args.stopwords = match args.stopwords {
+                Some(_) => None,
+                None => Stopwords::builtin("en").map(Arc::new),
+            };
+        }
+        let sources = self.last_source.borrow().clone();
+        self.start_analyze(sources);
+    }
+
+    fn menu_settings_gutenberg(&self) {
+        {
+            let mut args = self.args.borrow_mut();
+            args.strip_gutenberg = !args.strip_gutenberg;
+        }
+        let sources = self.last_source.borrow().clone();
+        self.start_analyze(sources);
+    }
+
+    fn menu_settings_lossy(&self) {
+        {
+            let mut args = self.args.borrow_mut();
+            args.lossy = !args.lossy;
+        }
+        let sources = self.last_source.borrow().clone();
+        self.start_analyze(sources);
+    }
+
+    fn menu_settings_wpm(&self) {
+        {
+            let mut args = self.args.borrow_mut();
+            args.wpm = !args.wpm;
+        }
+        let sources = self.last_source.borrow().clone();
+        self.start_analyze(sources);
+    }
+
     fn timertick(&self) {
         let tr = self.tr.borrow().clone().unwrap();
         while let Ok(message) = tr.try_recv() {
@@ -492,10 +588,29 @@ fn main() {
         bottom_words:    3,
         recursive:       true,
         follow_symlinks: false,
+        include:         Vec::new(),
+        exclude:         Vec::new(),
+        extensions:      Vec::new(),
+        no_ignore:       false,
+        hidden:          false,
         hide_empty:      true,
         outfile:         None,
         emojis:          false,
         show_all_words:  true,
+        ngrams:          0,
+        stopwords:       None,
+        encoding:        None,
+        lossy:           false,
+        binary:          false,
+        input_format:    None,
+        wpm:             false,
+        code_mode:       None,
+        strip_gutenberg: false,
+        tokenizer:       None,
+        stemmer:         None,
+        normalize:       None,
+        casefold:        false,
+        strip_accents:   false,
     };
     (*app.pwd.borrow_mut()) =
         canonicalize(std::env::current_dir().unwrap_or_else(|_| PathBuf::new()))
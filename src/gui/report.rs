@@ -5,23 +5,36 @@ use pathdiff::diff_paths;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use regex::{Regex, RegexBuilder};
 
-use super::shared::{Analysis, Args};
+use wordstat::{Analysis, Args, UniqueString};
 
-pub fn analysis_words_to_string(
-    analysis: &Analysis, top_words: usize, bottom_words: usize, emojis: bool,
+/// Appends the surface forms that were counted as a stemmed word if they differ from it.
+fn push_forms(buffer: &mut String, analysis: Option<&Analysis>, word: &UniqueString) {
+    let Some(forms) = analysis.map(|analysis| analysis.word_forms(word)) else {
+        return;
+    };
+    if forms.len() > 1 || forms.first().is_some_and(|form| form != word) {
+        let forms = forms.iter().map(UniqueString::as_str).collect::<Vec<_>>();
+        buffer.push_str(&format!(" ({})", forms.join(", ")));
+    }
+}
+
+pub fn frequencies_to_string(
+    frequencies: &[(usize, UniqueString)], forms: Option<&Analysis>, top_words: usize,
+    bottom_words: usize, emojis: bool,
 ) -> (String, String) {
-    if analysis.word_freq.is_empty() {
+    if frequencies.is_empty() {
         return ("".to_owned(), "".to_owned());
     }
-    let pad = format!("{}", analysis.word_freq[0].0).len();
+    let pad = format!("{}", frequencies[0].0).len();
     let mut buffer = String::new();
-    for (i, (freq, string)) in analysis.word_freq.iter().enumerate() {
+    for (i, (freq, string)) in frequencies.iter().enumerate() {
         if top_words > 0 && i >= top_words {
             break;
         };
         buffer.push_str(&format!("  {:width$}", freq, width = pad));
         buffer.push_str(": ");
         buffer.push_str(string);
+        push_forms(&mut buffer, forms, string);
         if emojis {
             if let Some(e) = emojis::get_by_shortcode(&string.to_lowercase()) {
                 buffer.push_str(format!(" {}", e).trim());
@@ -30,20 +43,16 @@ pub fn analysis_words_to_string(
         buffer.push('\n');
     }
     let mut buffer_bottom = String::new();
-    if bottom_words > 0
-        && top_words != 0
-        && top_words < analysis.word_count
-        && top_words < analysis.word_freq.len()
-    {
-        let pad =
-            format!("{}", analysis.word_freq.iter().nth_back(0).map(|n| n.0).unwrap_or(0)).len();
-        for (i, (freq, string)) in analysis.word_freq.iter().rev().enumerate() {
+    if bottom_words > 0 && top_words != 0 && top_words < frequencies.len() {
+        let pad = format!("{}", frequencies.iter().nth_back(0).map(|n| n.0).unwrap_or(0)).len();
+        for (i, (freq, string)) in frequencies.iter().rev().enumerate() {
             if bottom_words > 0 && i >= bottom_words {
                 break;
             };
             buffer_bottom.push_str(&format!("  {:width$}", freq, width = pad));
             buffer_bottom.push_str(": ");
             buffer_bottom.push_str(string);
+            push_forms(&mut buffer_bottom, forms, string);
 
             if emojis {
                 if let Some(e) = emojis::get_by_shortcode(&string.to_lowercase()) {
@@ -61,25 +70,40 @@ pub fn analysis_to_string(
     search_regex: &Option<Regex>, emojis: bool,
 ) -> (String, usize, usize) {
     let mut buffer = String::new();
-    let filtered_word_count;
-    let (analysis_string, analysis_string_bottom) = if search_regex.is_none() {
-        filtered_word_count = analysis.word_freq.len();
-        analysis_words_to_string(analysis, top_words, bottom_words, emojis)
-    } else {
-        let regex = search_regex.as_ref().unwrap();
-        let mut tmp_analysis = analysis.clone();
-        tmp_analysis.word_freq.retain(|(_, string)| regex.is_match(string));
-        filtered_word_count = tmp_analysis.word_freq.len();
-        analysis_words_to_string(&tmp_analysis, top_words, bottom_words, emojis)
+    let filter = |frequencies: &[(usize, UniqueString)]| match search_regex {
+        Some(regex) => frequencies
+            .iter()
+            .filter(|(_, string)| regex.is_match(string))
+            .cloned()
+            .collect::<Vec<_>>(),
+        None => frequencies.to_vec(),
     };
-    if analysis_string.is_empty() && hide_empty {
+    let word_freq = filter(&analysis.word_freq);
+    let filtered_word_count = word_freq.len();
+    let (analysis_string, analysis_string_bottom) =
+        frequencies_to_string(&word_freq, Some(analysis), top_words, bottom_words, emojis);
+    let ngram_freq = filter(&analysis.ngram_freq);
+    let filtered_ngram_count = ngram_freq.len();
+    let (ngram_string, ngram_string_bottom) =
+        frequencies_to_string(&ngram_freq, None, top_words, bottom_words, false);
+    if analysis_string.is_empty() && ngram_string.is_empty() && hide_empty {
         return (buffer, 0, 0);
     }
+    if let Some(encoding) = analysis.encoding.filter(|encoding| encoding.name() != "UTF-8") {
+        buffer.push_str(&format!("🔤 Encoding: {}\n", encoding.name()));
+    }
+    if analysis.replaced_bytes > 0 {
+        buffer.push_str(&format!("⚠️ Replaced bytes: {}\n", analysis.replaced_bytes));
+    }
     buffer.push_str(&format!("🔢 Word count: {}\n", analysis.word_count));
     buffer.push_str(&format!("🔢 Sentence count: {}\n", analysis.sent_count));
     buffer.push_str(&format!("🔢 Character count: {}\n", analysis.char_count));
     buffer.push_str(&format!("🔢 Paragraph count: {}\n", analysis.para_count));
     buffer.push_str(&format!("🔢 Unique words: {}\n", analysis.word_uniqs));
+    if analysis.stopword_count > 0 {
+        buffer.push_str(&format!("🔢 Stopword count: {}\n", analysis.stopword_count));
+        buffer.push_str(&format!("🔢 Unique stopwords: {}\n", analysis.stopword_uniqs));
+    }
     buffer.push_str(&format!("📊 Word frequency mean: {:.2}\n", analysis.word_dist_mean));
     buffer.push_str(&format!(
         "📊 Word frequency standard deviation: {:.2}\n",
@@ -87,6 +111,19 @@ pub fn analysis_to_string(
     ));
     buffer.push_str(&format!("📊 Word frequency median: {:.1}\n", analysis.word_dist_median));
     buffer.push_str(&format!("📊 Word frequency mode: {:.1}\n", analysis.word_dist_mode));
+    for (name, score) in [
+        ("Flesch reading ease", analysis.flesch_reading_ease),
+        ("Flesch-Kincaid grade level", analysis.flesch_kincaid_grade),
+        ("Gunning fog index", analysis.gunning_fog),
+        ("SMOG grade", analysis.smog),
+        ("Coleman-Liau index", analysis.coleman_liau),
+        ("Automated readability index", analysis.automated_readability),
+    ] {
+        buffer.push_str(&format!("📖 {}: {:.1}\n", name, score));
+    }
+    if analysis.words_per_minute > 0.0 {
+        buffer.push_str(&format!("⏱️ Words per minute: {:.1}\n", analysis.words_per_minute));
+    }
     if search_regex.is_some() {
         buffer.push_str(&format!("🔎 Words matching filter: {}\n", filtered_word_count));
     }
@@ -112,6 +149,28 @@ pub fn analysis_to_string(
             buffer.push_str(&analysis_string_bottom);
         }
     };
+    if !analysis.ngram_freq.is_empty() {
+        buffer.push_str(&format!("🔢 N-gram count: {}\n", analysis.ngram_count));
+        if search_regex.is_some() {
+            buffer.push_str(&format!("🔎 N-grams matching filter: {}\n", filtered_ngram_count));
+        }
+        if !ngram_string.is_empty() {
+            buffer.push_str("📈 Top n-grams");
+            if !search_regex.is_none() {
+                buffer.push_str(" (filtered)")
+            }
+            buffer.push_str(":\n");
+            buffer.push_str(&ngram_string);
+            if !ngram_string_bottom.is_empty() {
+                buffer.push_str("📉 Bottom n-grams");
+                if !search_regex.is_none() {
+                    buffer.push_str(" (filtered)")
+                }
+                buffer.push_str(":\n");
+                buffer.push_str(&ngram_string_bottom);
+            }
+        }
+    }
     (
         buffer,
         analysis_string.lines().count() + analysis_string_bottom.lines().count(),
@@ -191,6 +250,9 @@ pub fn get_result_text(
     }
 
     if let Some(ref analysis) = total {
+        if analysis.skipped_binary > 0 {
+            buffer.push_str(&format!("⚠️ Skipped binary files: {}\n\n", analysis.skipped_binary));
+        }
         let (mut printed_total, mut filtered_word_count) = (0, 0);
         if results_count > 1 {
             let (analysis_string, _printed_total, _filtered_word_count) = analysis_to_string(
@@ -0,0 +1,43 @@
+//! Word counter and text statistics.
+//!
+//! The main entry point is [`analyze`], which reads the given [`AnalyzeSource`]s in parallel and
+//! returns an [`Analysis`] per file together with a combined analysis of all files.
+//!
+//! ```no_run
+//! use wordstat::{analyze, AnalyzeSource, Args};
+//!
+//! let args = Args {
+//!     lowercase: true,
+//!     ..Default::default()
+//! };
+//! let (analyses, total) = analyze(
+//!     &[AnalyzeSource::Content(
+//!         "The quick brown fox jumps over the lazy dog.".into(),
+//!     )],
+//!     &args,
+//!     &std::env::current_dir().unwrap(),
+//!     |error| eprintln!("{}", error),
+//!     |_| (),
+//!     |_| (),
+//!     |_| (),
+//! );
+//! assert_eq!(analyses[0].word_count, 9);
+//! assert_eq!(total.unwrap().word_uniqs, 8);
+//! ```
+//!
+//! Words are interned in an [`Interner`] per call of [`analyze`] that every [`UniqueString`] keeps
+//! alive, its memory is released once all analyses and words of the call are dropped. The words
+//! are compared by the address of their entry in the interner and can be read as a `str` or
+//! `CStr`, the layout of the entries is not part of the public interface.
+
+mod shared;
+
+pub use shared::{
+    analyze, builtin_tokenizer, syllables, write_csv, write_json, write_tsv, Analysis,
+    AnalyzeSource, Args, CodeMode, Encoding, IdentifierTokenizer, IdentityHashSet, InputFormat,
+    Interner, Normalization, RegexTokenizer, Stemmer, Stopwords, Tokenizer, UnicodeTokenizer,
+    UniqueString, WhitespaceTokenizer, WordFormsMap, WordFreqMap, TOKENIZERS,
+};
+// Only public as the hasher of the word maps, which can't name a crate-private type
+#[doc(hidden)]
+pub use shared::IdentityHasher;
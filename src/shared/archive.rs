@@ -0,0 +1,123 @@
+use std::{
+    ffi::OsString,
+    fs::File,
+    io::{Cursor, Error, ErrorKind, Read, Result},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tar::Archive as TarArchive;
+use zip::ZipArchive;
+
+use super::{
+    compress::{self, decompress, uncompressed_path},
+    counter::Counter,
+    decode::{decode, Decoded},
+    document::read_document,
+    process_file,
+    walk::member_filter,
+    Analysis, Args, InputFormat, Interner,
+};
+
+/// Analyses of the files in an archive, and errors of the files that couldn't be analyzed.
+type Analyses = Vec<std::result::Result<Analysis, (PathBuf, Error)>>;
+
+/// Returns whether a file is a zip or tar archive, with tar archives optionally compressed.
+pub fn is_archive(path: &Path) -> bool {
+    let is = |path: &Path, extensions: &[&str]| {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extensions.iter().any(|e| e.eq_ignore_ascii_case(extension)))
+    };
+    is(path, &["zip", "tgz", "tbz2", "txz"]) || is(&uncompressed_path(path), &["tar"])
+}
+
+/// Returns the label of an archive member, the path of the archive and the path of the member
+/// separated by `!/`.
+fn member_label(archive: &Path, member: &Path) -> PathBuf {
+    let mut label = OsString::from(archive.as_os_str());
+    label.push("!/");
+    label.push(member.as_os_str());
+    label.into()
+}
+
+/// Decodes the content of an archive member and pushes it to the counter.
+fn read_member<R: Read>(
+    mut member: R, name: &Path, format: InputFormat, args: &Args, counter: &mut Counter,
+) -> Result<Decoded> {
+    if format.is_document() {
+        let mut content = Vec::new();
+        member.read_to_end(&mut content)?;
+        return read_document(Cursor::new(content), format, args, counter);
+    }
+    decode(decompress(member, name)?, args.encoding, args.binary, args.lossy, |text| {
+        counter.push(text)
+    })
+}
+
+fn read_zip(
+    path: &Path, args: &Arc<Args>, interner: &Interner, analyses: &mut Analyses,
+) -> Result<()> {
+    let filter = member_filter(args).map_err(|error| Error::new(ErrorKind::InvalidInput, error))?;
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    for index in 0..archive.len() {
+        let member = archive.by_index(index)?;
+        let Some(name) = member.enclosed_name().map(Path::to_path_buf) else {
+            continue;
+        };
+        if member.is_dir() || !filter(&name) {
+            continue;
+        }
+        let label = member_label(path, &name);
+        analyses.push(process_file(
+            label,
+            args.clone(),
+            interner.clone(),
+            |format, args, counter| read_member(member, &name, format, args, counter),
+        ));
+    }
+    Ok(())
+}
+
+fn read_tar(
+    path: &Path, args: &Arc<Args>, interner: &Interner, analyses: &mut Analyses,
+) -> Result<()> {
+    let filter = member_filter(args).map_err(|error| Error::new(ErrorKind::InvalidInput, error))?;
+    let mut archive = TarArchive::new(compress::open(path)?);
+    for member in archive.entries()? {
+        let member = member?;
+        if !member.header().entry_type().is_file() {
+            continue;
+        }
+        let name = member.path()?.into_owned();
+        if !filter(&name) {
+            continue;
+        }
+        let label = member_label(path, &name);
+        analyses.push(process_file(
+            label,
+            args.clone(),
+            interner.clone(),
+            |format, args, counter| read_member(member, &name, format, args, counter),
+        ));
+    }
+    Ok(())
+}
+
+/// Analyzes every file in a zip or tar archive as if the archive was a directory.
+///
+/// The files are labeled with the path of the archive and their path inside of it, like
+/// `archive.zip!/chapter1.txt`. An archive that can't be read results in an error for the archive,
+/// after the analyses of the files that were read before the error.
+pub fn read_archive(path: &Path, args: Arc<Args>, interner: Interner) -> Analyses {
+    let mut analyses = Vec::new();
+    let is_zip = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("zip"));
+    let result = match is_zip {
+        true => read_zip(path, &args, &interner, &mut analyses),
+        false => read_tar(path, &args, &interner, &mut analyses),
+    };
+    if let Err(error) = result {
+        analyses.push(Err((path.to_path_buf(), error)));
+    }
+    analyses
+}
@@ -0,0 +1,78 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Result},
+    path::{Path, PathBuf},
+};
+
+use bzip2::read::MultiBzDecoder;
+use flate2::bufread::MultiGzDecoder;
+use xz2::bufread::XzDecoder;
+use zstd::stream::read::Decoder as ZstdDecoder;
+
+/// Compression format of a file that is decompressed before it is analyzed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+impl Compression {
+    /// Returns the compression format of a file by its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension().and_then(|extension| extension.to_str())?;
+        match extension.to_ascii_lowercase().as_str() {
+            "gz" | "gzip" => Some(Self::Gzip),
+            "zst" | "zstd" => Some(Self::Zstd),
+            "bz2" | "bzip2" => Some(Self::Bzip2),
+            "xz" => Some(Self::Xz),
+            _ => None,
+        }
+    }
+
+    /// Returns the compression format of a file by the magic bytes at its start.
+    pub fn from_magic(start: &[u8]) -> Option<Self> {
+        if start.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if start.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else if start.starts_with(b"BZh") {
+            Some(Self::Bzip2)
+        } else if start.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(Self::Xz)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the path without the extension of its compression format, so `notes.md.gz` is
+/// `notes.md`.
+pub fn uncompressed_path(path: &Path) -> PathBuf {
+    match Compression::from_path(path) {
+        Some(_) => path.with_extension(""),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Returns a reader of the decompressed content of a file if its extension or magic bytes show
+/// that it is compressed with gzip, zstd, bzip2 or xz.
+pub fn decompress<'a, R: Read + 'a>(reader: R, path: &Path) -> Result<Box<dyn Read + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let compression = match Compression::from_path(path) {
+        Some(compression) => Some(compression),
+        None => Compression::from_magic(reader.fill_buf()?),
+    };
+    Ok(match compression {
+        None => Box::new(reader),
+        Some(Compression::Gzip) => Box::new(MultiGzDecoder::new(reader)),
+        Some(Compression::Zstd) => Box::new(ZstdDecoder::with_buffer(reader)?),
+        Some(Compression::Bzip2) => Box::new(MultiBzDecoder::new(reader)),
+        Some(Compression::Xz) => Box::new(XzDecoder::new_multi_decoder(reader)),
+    })
+}
+
+/// Opens a file for reading, decompressing it if it is compressed.
+pub fn open(path: &Path) -> Result<Box<dyn Read>> {
+    decompress(File::open(path)?, path)
+}
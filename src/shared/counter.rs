@@ -0,0 +1,538 @@
+use std::{borrow::Cow, path::PathBuf, sync::Arc};
+
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{
+    format::Extractor,
+    normalize::{casefold, strip_diacritics},
+    readability::{syllables, update_readability},
+    sorted_freq, stopwords,
+    tokenizer::{Tokenizer, UnicodeTokenizer},
+    update_dists, update_words_per_minute, Analysis, Args, Interner,
+};
+
+/// Size of the buffered text after which its complete sentences are counted.
+#[cfg(not(test))]
+const SEGMENT_SIZE: usize = 1 << 20;
+/// Size of the buffered text after which its complete sentences are counted, small in tests so
+/// that short texts are cut into many segments.
+#[cfg(test)]
+const SEGMENT_SIZE: usize = 64;
+/// Size of the buffered text after which it is cut at a word boundary if it has no sentence
+/// boundaries.
+const SEGMENT_LIMIT: usize = SEGMENT_SIZE * 16;
+
+#[derive(Default, Clone, Copy)]
+struct Counts {
+    words:         usize,
+    stopwords:     usize,
+    syllables:     usize,
+    polysyllables: usize,
+    letters:       usize,
+}
+impl Counts {
+    fn add_readability(&mut self, word: &str) {
+        let syllables = syllables(word);
+        self.syllables += syllables;
+        self.polysyllables += (syllables >= 3) as usize;
+        self.letters += word.chars().filter(|c| c.is_alphanumeric()).count();
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            words:         self.words + other.words,
+            stopwords:     self.stopwords + other.stopwords,
+            syllables:     self.syllables + other.syllables,
+            polysyllables: self.polysyllables + other.polysyllables,
+            letters:       self.letters + other.letters,
+        }
+    }
+
+    fn apply(self, analysis: &mut Analysis) {
+        analysis.word_count += self.words;
+        analysis.stopword_count += self.stopwords;
+        analysis.syllable_count += self.syllables;
+        analysis.polysyllable_count += self.polysyllables;
+        analysis.letter_count += self.letters;
+    }
+}
+
+/// Returns the byte offset of a slice of the text, or `None` if it is not part of the text.
+fn offset(text: &str, slice: &str) -> Option<usize> {
+    let offset = (slice.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+    (offset + slice.len() <= text.len()).then_some(offset)
+}
+
+/// Applies a function to a borrowed or owned word, keeping the owned word if it is unchanged.
+fn map_word<'a>(word: Cow<'a, str>, map: impl Fn(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    match word {
+        Cow::Borrowed(word) => map(word),
+        Cow::Owned(word) => match map(&word) {
+            Cow::Owned(mapped) => Cow::Owned(mapped),
+            Cow::Borrowed(_) => Cow::Owned(word),
+        },
+    }
+}
+
+/// Folds the case of a word, strips its diacritics and normalizes it as enabled, so that all
+/// representations of a word are interned as the same string.
+fn fold_word<'a>(word: &'a str, args: &Args) -> Cow<'a, str> {
+    let mut word = Cow::Borrowed(word);
+    if args.casefold {
+        word = map_word(word, casefold);
+    } else if args.lowercase {
+        word = Cow::Owned(word.to_lowercase());
+    }
+    if args.strip_accents {
+        word = map_word(word, strip_diacritics);
+    }
+    if let Some(normalization) = args.normalize {
+        word = map_word(word, |word| normalization.normalize(word));
+    }
+    word
+}
+
+fn stem_word<'a>(word: Cow<'a, str>, args: &'a Args) -> Cow<'a, str> {
+    match (&args.stemmer, word) {
+        (Some(stemmer), Cow::Borrowed(word)) => stemmer.stem(word),
+        (Some(stemmer), Cow::Owned(word)) => Cow::Owned(stemmer.stem(&word).into_owned()),
+        (None, word) => word,
+    }
+}
+
+/// Counts text that is pushed in pieces of any size.
+///
+/// Pushed content is passed through the extractor of its format if there is one, the text is
+/// then buffered until it contains complete sentences, so that words, sentences and
+/// paragraphs are counted the same as for the whole text while memory use stays proportional to
+/// the vocabulary instead of the length of the text.
+pub struct Counter {
+    args:        Arc<Args>,
+    interner:    Interner,
+    extractor:   Option<Box<dyn Extractor>>,
+    analysis:    Analysis,
+    counts:      Counts,
+    pending:     String,
+    /// Length of the buffered text at which it is next searched for boundaries
+    next_cut:    usize,
+    /// Whether the pending text continues a sentence that was cut at a word boundary
+    continued:   bool,
+    para_breaks: usize,
+    /// Whitespace at the end of the counted text, which can start a paragraph separator
+    para_carry:  String,
+    /// Last words of the counted text with whether they are stopwords, which start the n-grams of
+    /// the next text if it continues the same sentence
+    ngram_carry: Vec<(bool, String)>,
+}
+impl Counter {
+    pub fn new(
+        file: Option<PathBuf>, args: Arc<Args>, interner: Interner,
+        extractor: Option<Box<dyn Extractor>>,
+    ) -> Self {
+        Self {
+            analysis: Analysis {
+                file,
+                ..Default::default()
+            },
+            args,
+            interner,
+            extractor,
+            counts: Counts::default(),
+            pending: String::new(),
+            next_cut: SEGMENT_SIZE,
+            continued: false,
+            para_breaks: 0,
+            para_carry: String::new(),
+            ngram_carry: Vec::new(),
+        }
+    }
+
+    /// Extracts and buffers the text of the content.
+    pub fn push(&mut self, content: &str) {
+        match &mut self.extractor {
+            Some(extractor) => {
+                let mut text = String::new();
+                extractor.push(content, &mut text);
+                self.buffer(&text);
+            }
+            None => self.buffer(content),
+        }
+    }
+
+    /// Buffers the text and counts all sentences that can't be changed by text that follows.
+    fn buffer(&mut self, text: &str) {
+        self.pending.push_str(text);
+        if self.pending.len() < self.next_cut {
+            return;
+        }
+
+        // Boundaries are only final with text after them, so the last two sentences or words are kept
+        let tokenizer: &dyn Tokenizer = self.args.tokenizer.as_deref().unwrap_or(&UnicodeTokenizer);
+        let sentences = tokenizer.sentences(&self.pending);
+        let second_last = match sentences.len() {
+            3.. => offset(&self.pending, sentences[sentences.len() - 2]),
+            _ => None,
+        };
+        let cut = if second_last.is_some() {
+            second_last
+        } else if self.pending.len() >= SEGMENT_LIMIT {
+            self.pending.split_word_bound_indices().rev().nth(1).map(|(start, _)| start)
+        } else {
+            None
+        };
+        let Some(cut) = cut.filter(|&cut| cut > 0) else {
+            self.next_cut = match self.pending.len() < SEGMENT_LIMIT {
+                true => (self.pending.len() * 2).min(SEGMENT_LIMIT),
+                false => self.pending.len() * 2,
+            };
+            return;
+        };
+
+        let rest = self.pending.split_off(cut);
+        let segment = std::mem::replace(&mut self.pending, rest);
+        self.count(&segment);
+        self.continued = Some(cut) != second_last;
+        self.next_cut = self.pending.len() + SEGMENT_SIZE;
+    }
+
+    /// Counts the remaining buffered text and returns the analysis.
+    pub fn finish(mut self) -> Analysis {
+        if let Some(mut extractor) = self.extractor.take() {
+            let mut text = String::new();
+            extractor.finish(&mut text);
+            self.pending.push_str(&text);
+            self.analysis.cue_duration = extractor.duration();
+        }
+        if !self.pending.is_empty() {
+            let segment = std::mem::take(&mut self.pending);
+            self.count(&segment);
+        }
+
+        let mut analysis = self.analysis;
+        analysis.para_count = self.para_breaks + 1;
+        analysis.word_freq = sorted_freq(&analysis.word_freq_map);
+        self.counts.apply(&mut analysis);
+        update_dists(&mut analysis);
+        update_readability(&mut analysis);
+        if analysis.cue_duration > 0.0 {
+            analysis.cue_word_count = analysis.all_word_count;
+        }
+        if self.args.wpm {
+            update_words_per_minute(&mut analysis);
+        }
+        if self.args.ngrams > 1 {
+            analysis.ngram_freq = sorted_freq(&analysis.ngram_freq_map);
+        }
+        analysis
+    }
+
+    /// Counts text that ends at a sentence boundary, or at a word boundary if it is continued.
+    fn count(&mut self, text: &str) {
+        let (args, interner, analysis) = (&self.args, &self.interner, &mut self.analysis);
+        let tokenizer: &dyn Tokenizer = args.tokenizer.as_deref().unwrap_or(&UnicodeTokenizer);
+
+        let sentences = tokenizer.sentences(text);
+        let continued = std::mem::take(&mut self.continued);
+        analysis.sent_count += sentences.len().saturating_sub(continued as usize);
+        analysis.char_count += text.graphemes(true).count();
+        // Separators at the end of the counted text are counted again with the text that follows
+        let mut para_text = std::mem::take(&mut self.para_carry);
+        let carried_breaks = tokenizer.paragraphs(&para_text).len().saturating_sub(1);
+        para_text.push_str(text);
+        let breaks = tokenizer.paragraphs(&para_text).len().saturating_sub(1);
+        self.para_breaks += breaks.saturating_sub(carried_breaks);
+        self.para_carry = para_text.split_off(para_text.trim_end().len());
+
+        let is_stopword =
+            |word: &str| args.stopwords.as_ref().is_some_and(|stopwords| stopwords.contains(word));
+
+        let words = tokenizer.words(text);
+        let counts = words
+            .par_iter()
+            .chunks(12500)
+            .map(|words| {
+                let mut counts = Counts::default();
+                for &word in words.iter() {
+                    counts.add_readability(word);
+                    let word = fold_word(word, args);
+                    let (map, key) = if is_stopword(&word) {
+                        counts.stopwords += 1;
+                        (&analysis.stopword_freq_map, interner.intern(&stopwords::normalize(&word)))
+                    } else if let Some(stemmer) = &args.stemmer {
+                        counts.words += 1;
+                        let stem = interner.intern(&stemmer.stem(&word));
+                        let form = interner.intern(&word);
+                        analysis.word_forms_map.entry(stem.clone()).or_default().insert(form);
+                        (&analysis.word_freq_map, stem)
+                    } else {
+                        counts.words += 1;
+                        (&analysis.word_freq_map, interner.intern(&word))
+                    };
+                    map.entry(key).and_modify(|num| *num += 1).or_insert(1);
+                }
+                counts
+            })
+            .reduce(Counts::default, Counts::merge);
+        self.counts = self.counts.merge(counts);
+
+        if args.ngrams > 1 {
+            // The first sentence continues the words carried over from a sentence that was cut
+            let carry = std::mem::take(&mut self.ngram_carry);
+            let sentence_words = |index: usize| {
+                let carry = carry.iter().filter(|_| continued && index == 0);
+                carry
+                    .map(|(stopword, word)| (*stopword, Cow::Borrowed(word.as_str())))
+                    .chain(tokenizer.words(sentences[index]).into_iter().map(|word| {
+                        let word = fold_word(word, args);
+                        (is_stopword(&word), stem_word(word, args))
+                    }))
+                    .collect::<Vec<_>>()
+            };
+            analysis.ngram_count += (0..sentences.len())
+                .into_par_iter()
+                .map(|index| {
+                    let words = sentence_words(index);
+                    let mut count = 0;
+                    for ngram in words.windows(args.ngrams) {
+                        if ngram.iter().all(|(stopword, _)| *stopword) {
+                            continue;
+                        }
+                        count += 1;
+                        let ngram = ngram.iter().map(|(_, word)| word.as_ref()).collect::<Vec<_>>();
+                        analysis
+                            .ngram_freq_map
+                            .entry(interner.intern(&ngram.join(" ")))
+                            .and_modify(|num| *num += 1)
+                            .or_insert(1);
+                    }
+                    count
+                })
+                .sum::<usize>();
+            if !sentences.is_empty() {
+                let words = sentence_words(sentences.len() - 1);
+                self.ngram_carry = words[words.len().saturating_sub(args.ngrams - 1)..]
+                    .iter()
+                    .map(|(stopword, word)| (*stopword, word.to_string()))
+                    .collect();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::shared::{Normalization, Stemmer};
+
+    fn word_counts(analysis: &Analysis) -> HashMap<String, usize> {
+        analysis
+            .word_freq
+            .iter()
+            .map(|(count, word)| (word.to_string(), *count))
+            .collect()
+    }
+
+    /// Counts the text in a single pass without cutting it into segments.
+    fn count_whole(text: &str, args: &Arc<Args>) -> Analysis {
+        let mut counter = Counter::new(None, args.clone(), Interner::new(), None);
+        counter.count(text);
+        counter.finish()
+    }
+
+    /// Counts the text pushed in pieces of up to `size` characters.
+    fn count_streamed(text: &str, args: &Arc<Args>, size: usize) -> Analysis {
+        let mut counter = Counter::new(None, args.clone(), Interner::new(), None);
+        let chars = text.char_indices().map(|(index, _)| index).collect::<Vec<_>>();
+        for piece in chars.chunks(size) {
+            let end = chars.get(chars.partition_point(|&index| index <= piece[piece.len() - 1]));
+            counter.push(&text[piece[0]..end.copied().unwrap_or(text.len())]);
+        }
+        counter.finish()
+    }
+
+    fn ngram_counts(analysis: &Analysis) -> HashMap<String, usize> {
+        analysis
+            .ngram_freq
+            .iter()
+            .map(|(count, ngram)| (ngram.to_string(), *count))
+            .collect()
+    }
+
+    fn assert_streamed_equals_whole(text: &str) {
+        let args = Args {
+            ngrams: 2,
+            ..Default::default()
+        };
+        assert_streamed_equals_whole_with(text, &Arc::new(args));
+    }
+
+    fn assert_streamed_equals_whole_with(text: &str, args: &Arc<Args>) {
+        let whole = count_whole(text, args);
+        for size in [1, 3, 7, 50, 200, text.len()] {
+            let streamed = count_streamed(text, args, size);
+            assert_eq!(streamed.word_count, whole.word_count, "words with pieces of {}", size);
+            assert_eq!(streamed.sent_count, whole.sent_count, "sentences with pieces of {}", size);
+            assert_eq!(streamed.para_count, whole.para_count, "paragraphs with pieces of {}", size);
+            assert_eq!(streamed.char_count, whole.char_count, "characters with pieces of {}", size);
+            assert_eq!(streamed.ngram_count, whole.ngram_count, "n-grams with pieces of {}", size);
+            assert_eq!(word_counts(&streamed), word_counts(&whole));
+            assert_eq!(ngram_counts(&streamed), ngram_counts(&whole));
+        }
+    }
+
+    #[test]
+    fn streams_sentences() {
+        let text = "The first sentence is short. Is the second one a question? Yes!\r\n\r\n"
+            .repeat(20)
+            + "A last paragraph without a terminating newline";
+        assert_streamed_equals_whole(&text);
+    }
+
+    #[test]
+    fn streams_text_without_sentence_boundaries() {
+        let text = "words without any sentence boundary and without line breaks ".repeat(60);
+        assert!(text.len() > SEGMENT_LIMIT * 2);
+        assert_streamed_equals_whole(&text);
+    }
+
+    #[test]
+    fn streams_multibyte_characters() {
+        let text = "Über Straßen fährt café — naïve Wörter. 日本語の文章です。Ελληνικά λόγια; \
+                    emoji 👩‍👩‍👧 families.\n\n"
+            .repeat(30);
+        assert_streamed_equals_whole(&text);
+        let text = "ééééé ñññññ ööööö 語語語語 ".repeat(100);
+        assert_streamed_equals_whole(&text);
+    }
+
+    #[test]
+    fn counts_paragraphs_of_tokenizer() {
+        /// Tokenizer of paragraphs that are single lines.
+        #[derive(Debug)]
+        struct LineTokenizer;
+        impl Tokenizer for LineTokenizer {
+            fn words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+                text.split_whitespace().collect()
+            }
+
+            fn paragraphs<'a>(&self, text: &'a str) -> Vec<&'a str> {
+                text.split('\n').collect()
+            }
+        }
+
+        let args = Arc::new(Args {
+            tokenizer: Some(Arc::new(LineTokenizer)),
+            ..Default::default()
+        });
+        let text = "One line. Another sentence.\nSecond line\n\nFourth line.\n".repeat(10);
+        assert_eq!(count_whole(&text, &args).para_count, 41);
+        for size in [1, 3, 7, 50, 200] {
+            assert_eq!(count_streamed(&text, &args, size).para_count, 41);
+        }
+    }
+
+    #[test]
+    fn streams_sentences_of_tokenizer() {
+        /// Tokenizer of sentences that are single lines.
+        #[derive(Debug)]
+        struct LineTokenizer;
+        impl Tokenizer for LineTokenizer {
+            fn words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+                text.unicode_words().collect()
+            }
+
+            fn sentences<'a>(&self, text: &'a str) -> Vec<&'a str> {
+                text.lines().filter(|line| !line.trim().is_empty()).collect()
+            }
+        }
+
+        let args = Arc::new(Args {
+            tokenizer: Some(Arc::new(LineTokenizer)),
+            ngrams: 2,
+            ..Default::default()
+        });
+        let text = "The first line. It has two sentences\nA second line that is longer than a \
+                    segment of the tests. Still the same line\n\nShort\n"
+            .repeat(10);
+        assert_eq!(count_whole(&text, &args).sent_count, 30);
+        assert_streamed_equals_whole_with(&text, &args);
+    }
+
+    #[test]
+    fn folds_words_as_enabled() {
+        let fold = |word: &str, configure: fn(&mut Args)| {
+            let mut args = Args::default();
+            configure(&mut args);
+            fold_word(word, &args).into_owned()
+        };
+        assert_eq!(fold("Café", |_| ()), "Café");
+        assert_eq!(fold("STRASSE", |args| args.lowercase = true), "strasse");
+        assert_eq!(fold("Straße", |args| args.casefold = true), "strasse");
+        assert_eq!(fold("Cafe\u{301}", |args| args.strip_accents = true), "Cafe");
+        assert_eq!(fold("हिन्दी", |args| args.strip_accents = true), "हिन्दी");
+        let casefold_and_strip =
+            |args: &mut Args| (args.casefold, args.strip_accents) = (true, true);
+        assert_eq!(fold("CAFÉ", casefold_and_strip), "cafe");
+        assert_eq!(fold("Cafe\u{301}", |args| args.normalize = Some(Normalization::Nfc)), "Café");
+    }
+
+    #[test]
+    fn collects_folded_forms_of_stems() {
+        let forms = |lowercase| {
+            let args = Args {
+                stemmer: Stemmer::snowball("english").map(Arc::new),
+                lowercase,
+                ..Default::default()
+            };
+            let mut counter = Counter::new(None, Arc::new(args), Interner::new(), None);
+            counter.push("Running runs. The runner was running; RUNS ran.");
+            let analysis = counter.finish();
+            let stem = analysis.word_freq.iter().find(|(_, word)| word.as_str() == "run");
+            assert_eq!(stem.map(|(count, _)| *count), Some(4));
+            let stem = stem.unwrap().1.clone();
+            analysis
+                .word_forms(&stem)
+                .iter()
+                .map(|form| form.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(forms(false), ["RUNS", "Running", "running", "runs"]);
+        assert_eq!(forms(true), ["running", "runs"]);
+    }
+
+    #[test]
+    fn counts_stopwords_regardless_of_case() {
+        let args = Args {
+            stopwords: stopwords::Stopwords::builtin("en").map(Arc::new),
+            ..Default::default()
+        };
+        let mut counter = Counter::new(None, Arc::new(args), Interner::new(), None);
+        counter.push("The cat and the dog. THE END");
+        let analysis = counter.finish();
+        assert_eq!(analysis.stopword_count, 4);
+        assert_eq!(analysis.stopword_uniqs, 2);
+        assert_eq!(analysis.word_uniqs, 3);
+    }
+
+    #[test]
+    fn counts_colliding_words_separately() {
+        let mut counter =
+            Counter::new(None, Arc::new(Args::default()), Interner::colliding(), None);
+        counter.push("one two one three one two");
+        let analysis = counter.finish();
+        assert_eq!(analysis.word_uniqs, 3);
+        assert_eq!(
+            word_counts(&analysis),
+            HashMap::from([
+                ("one".to_owned(), 3),
+                ("two".to_owned(), 2),
+                ("three".to_owned(), 1)
+            ])
+        );
+    }
+}
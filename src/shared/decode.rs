@@ -0,0 +1,230 @@
+use std::{
+    fmt::{Display, Formatter},
+    io::{Error, ErrorKind, Read, Result},
+    path::Path,
+};
+
+use chardetng::EncodingDetector;
+use encoding_rs::{DecoderResult, Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+use super::{compress, counter::Counter, Args};
+
+/// Size of the blocks that files are read in, the first block is used to detect the encoding.
+const READ_SIZE: usize = 1 << 16;
+
+/// Error of files that were not analyzed because their content is binary.
+#[derive(Debug)]
+struct BinaryFile;
+impl Display for BinaryFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("binary file")
+    }
+}
+impl std::error::Error for BinaryFile {}
+
+/// Returns whether the error is caused by a file being skipped because its content is binary.
+pub fn is_binary_error(error: &Error) -> bool {
+    error.get_ref().is_some_and(|error| error.is::<BinaryFile>())
+}
+
+/// Result of decoding a file.
+pub struct Decoded {
+    pub encoding:       &'static Encoding,
+    pub replaced_bytes: usize,
+}
+
+/// Returns the encoding of a byte order mark, of UTF-16 text without one, or the most likely
+/// legacy encoding of the first block of a file.
+fn detect_encoding(block: &[u8], last: bool) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(block) {
+        return encoding;
+    }
+    if let Some(encoding) = detect_utf16(block) {
+        return encoding;
+    }
+    if block.is_ascii() {
+        return UTF_8;
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(block, last);
+    detector.guess(None, true)
+}
+
+/// Detects UTF-16 text without a byte order mark by the zero high bytes of ASCII characters.
+fn detect_utf16(block: &[u8]) -> Option<&'static Encoding> {
+    let pairs = block.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+    let (mut even, mut odd) = (0, 0);
+    for pair in block.chunks_exact(2) {
+        even += (pair[0] == 0) as usize;
+        odd += (pair[1] == 0) as usize;
+    }
+    match (even, odd) {
+        (0, odd) if odd > pairs / 2 => Some(UTF_16LE),
+        (even, 0) if even > pairs / 2 => Some(UTF_16BE),
+        _ => None,
+    }
+}
+
+/// Returns whether a block contains NUL bytes or more than 10% control characters other than
+/// whitespace and escapes, which text in encodings other than UTF-16 doesn't.
+fn is_binary(block: &[u8]) -> bool {
+    let controls = block
+        .iter()
+        .filter(|&&byte| matches!(byte, 0..=8 | 14..=26 | 28..=31 | 127))
+        .count();
+    block.contains(&0) || controls * 10 > block.len()
+}
+
+/// Reads into the buffer until it is full or the end of the input is reached.
+fn read_block<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(filled)
+}
+
+/// Reads input in blocks, decodes it and passes the text to `push`.
+///
+/// The encoding is detected from the first block if it is not given. Malformed byte sequences are
+/// an error unless `lossy` is set, in which case they are replaced and counted. Binary input is an
+/// error unless `binary` is set.
+pub fn decode<R: Read>(
+    mut reader: R, encoding: Option<&'static Encoding>, binary: bool, lossy: bool,
+    mut push: impl FnMut(&str),
+) -> Result<Decoded> {
+    let mut buffer = vec![0; READ_SIZE];
+    let mut filled = read_block(&mut reader, &mut buffer)?;
+    let mut last = filled < buffer.len();
+
+    let encoding = encoding.unwrap_or_else(|| detect_encoding(&buffer[..filled], last));
+    if !binary && encoding != UTF_16LE && encoding != UTF_16BE && is_binary(&buffer[..filled]) {
+        return Err(Error::new(ErrorKind::InvalidData, BinaryFile));
+    }
+    let mut decoder = encoding.new_decoder_with_bom_removal();
+    let mut output = String::with_capacity(READ_SIZE * 3);
+    let mut replaced_bytes = 0;
+    loop {
+        let mut input = &buffer[..filled];
+        loop {
+            let (result, read) =
+                decoder.decode_to_string_without_replacement(input, &mut output, last);
+            input = &input[read..];
+            match result {
+                DecoderResult::InputEmpty => break,
+                DecoderResult::OutputFull => {
+                    push(&output);
+                    output.clear();
+                }
+                DecoderResult::Malformed(length, _) if lossy => {
+                    output.push(char::REPLACEMENT_CHARACTER);
+                    replaced_bytes += length as usize;
+                }
+                DecoderResult::Malformed(..) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("stream did not contain valid {}", encoding.name()),
+                    ));
+                }
+            }
+        }
+        push(&output);
+        output.clear();
+        if last {
+            break;
+        }
+        filled = read_block(&mut reader, &mut buffer)?;
+        last = filled < buffer.len();
+    }
+
+    Ok(Decoded {
+        encoding,
+        replaced_bytes,
+    })
+}
+
+/// Reads a file in blocks, decodes it and pushes the text to the counter.
+///
+/// Files compressed with gzip, zstd, bzip2 or xz are decompressed while they are read.
+/// The encoding is taken from [`Args::encoding`], or detected from the first block if it is not
+/// set. Malformed byte sequences are an error unless [`Args::lossy`] is set, in which case they
+/// are replaced and counted. Files with binary content are skipped unless [`Args::binary`] is set.
+pub fn read_file(path: &Path, args: &Args, counter: &mut Counter) -> Result<Decoded> {
+    decode(compress::open(path)?, args.encoding, args.binary, args.lossy, |text| {
+        counter.push(text)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::WINDOWS_1252;
+
+    use super::*;
+
+    fn decode_all(bytes: &[u8], binary: bool, lossy: bool) -> Result<(String, Decoded)> {
+        let mut text = String::new();
+        let decoded = decode(bytes, None, binary, lossy, |piece| text.push_str(piece))?;
+        Ok((text, decoded))
+    }
+
+    fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|unit| match big_endian {
+                true => unit.to_be_bytes(),
+                false => unit.to_le_bytes(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detects_binary_content() {
+        assert!(is_binary(b"text\0with a NUL byte"));
+        assert!(is_binary(&[1, 2, 3, 4, b'a', b'b', b'c', b'd', b'e', b'f']));
+        assert!(!is_binary(b"plain text\r\n\twith \x1b[1mescapes\x1b[0m\x0c"));
+        assert!(!is_binary("Unicode text – ünïcödé".as_bytes()));
+
+        let error = decode_all(b"\x7fELF\x02\x01\x01\0\0\0", false, false).err().unwrap();
+        assert!(is_binary_error(&error));
+        assert!(decode_all(b"\x7fELF\x02\x01\x01\0\0\0", true, false).is_ok());
+    }
+
+    #[test]
+    fn detects_utf16_without_byte_order_mark() {
+        let text = "Some words in UTF-16";
+        assert_eq!(detect_utf16(&utf16(text, false)), Some(UTF_16LE));
+        assert_eq!(detect_utf16(&utf16(text, true)), Some(UTF_16BE));
+        assert_eq!(detect_utf16(text.as_bytes()), None);
+        assert_eq!(detect_utf16(b"a"), None);
+
+        let (decoded, result) = decode_all(&utf16(text, false), false, false).unwrap();
+        assert_eq!(decoded, text);
+        assert_eq!(result.encoding, UTF_16LE);
+    }
+
+    #[test]
+    fn detects_byte_order_marks_and_legacy_encodings() {
+        let (text, result) = decode_all(b"\xef\xbb\xbfwith BOM", false, false).unwrap();
+        assert_eq!((text.as_str(), result.encoding), ("with BOM", UTF_8));
+        assert_eq!(detect_encoding(b"\xfe\xff\0a", true), UTF_16BE);
+        assert_eq!(detect_encoding(b"caf\xe9 cr\xe8me br\xfbl\xe9e", true), WINDOWS_1252);
+    }
+
+    #[test]
+    fn replaces_malformed_bytes_only_in_lossy_mode() {
+        let bytes: &[u8] = b"valid \xff\xfe invalid";
+        let mut text = String::new();
+        let strict = decode(bytes, Some(UTF_8), false, false, |_| ());
+        assert_eq!(strict.err().unwrap().kind(), ErrorKind::InvalidData);
+        let lossy = decode(bytes, Some(UTF_8), false, true, |piece| text.push_str(piece));
+        assert_eq!(lossy.unwrap().replaced_bytes, 2);
+        assert_eq!(text, "valid \u{fffd}\u{fffd} invalid");
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind, Read, Result, Seek},
+};
+
+use encoding_rs::UTF_8;
+use zip::ZipArchive;
+
+use super::{
+    counter::Counter,
+    decode::{decode, Decoded},
+    format::{Elements, Extractor, Markup, HTML},
+    Args, InputFormat,
+};
+
+/// Elements of EPUB content documents, without their head.
+const EPUB: Elements = Elements {
+    raw: &["head", "script", "style"],
+    ..HTML
+};
+
+/// Elements of the `word/document.xml` part of DOCX documents.
+const DOCX: Elements = Elements {
+    raw:       &["w:deltext", "w:instrtext"],
+    paragraph: &["w:p"],
+    line:      &["w:br", "w:cr"],
+    space:     &["w:tab"],
+    inline:    &[],
+    separate:  false,
+};
+
+/// Elements of the `content.xml` part of ODT documents.
+const ODT: Elements = Elements {
+    raw:       &["office:annotation", "text:tracked-changes"],
+    paragraph: &["text:h", "text:p"],
+    line:      &["text:line-break"],
+    space:     &["text:s", "text:tab"],
+    inline:    &[],
+    separate:  false,
+};
+
+fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Returns the tags of all elements with the name, ignoring namespace prefixes.
+fn tags<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    xml.split('<').skip(1).filter_map(move |tag| {
+        let tag = tag.split('>').next()?;
+        let element = tag.split(|c: char| c.is_whitespace() || c == '/').next()?;
+        (element.rsplit(':').next() == Some(name)).then_some(tag)
+    })
+}
+
+/// Returns the value of an attribute of a tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(index) = rest.find(name) {
+        let preceded = rest[..index].ends_with(char::is_whitespace);
+        rest = &rest[index + name.len()..];
+        let Some(value) = rest.trim_start().strip_prefix('=').filter(|_| preceded) else {
+            continue;
+        };
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|&c| c == '"' || c == '\'')?;
+        return value[1..].split(quote).next();
+    }
+    None
+}
+
+/// Decodes percent-encoded bytes and `&amp;` entities in a path of a package document.
+fn decode_href(href: &str) -> String {
+    let href = href.replace("&amp;", "&");
+    let mut bytes = Vec::with_capacity(href.len());
+    let mut rest = href.as_bytes();
+    while let Some((&byte, next)) = rest.split_first() {
+        let hex = next.get(..2).and_then(|hex| std::str::from_utf8(hex).ok());
+        match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()).filter(|_| byte == b'%') {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &next[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = next;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Returns the path of an archive entry referenced relative to the directory `base`.
+fn resolve(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or_default();
+    let mut segments = base.split('/').filter(|segment| !segment.is_empty()).collect::<Vec<_>>();
+    for segment in href.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<String> {
+    let mut content = String::new();
+    archive.by_name(name)?.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Returns the paths of the content documents of an EPUB in spine order.
+fn epub_spine<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Vec<String>> {
+    let container = read_entry(archive, "META-INF/container.xml")?;
+    let rootfile = tags(&container, "rootfile")
+        .find_map(|tag| attribute(tag, "full-path"))
+        .map(decode_href)
+        .ok_or_else(|| invalid("missing rootfile in META-INF/container.xml".to_string()))?;
+    let package = read_entry(archive, &rootfile)?;
+    let base = rootfile.rsplit_once('/').map_or("", |(base, _)| base);
+
+    let manifest = tags(&package, "item")
+        .filter_map(|tag| Some((attribute(tag, "id")?, attribute(tag, "href")?)))
+        .collect::<HashMap<_, _>>();
+    Ok(tags(&package, "itemref")
+        .filter_map(|tag| manifest.get(attribute(tag, "idref")?))
+        .map(|href| resolve(base, &decode_href(href)))
+        .collect())
+}
+
+/// Reads the body text of an EPUB, DOCX or ODT document and pushes it to the counter.
+///
+/// The chapters of EPUBs are read in spine order and separated as paragraphs.
+pub fn read_document<R: Read + Seek>(
+    reader: R, format: InputFormat, args: &Args, counter: &mut Counter,
+) -> Result<Decoded> {
+    let mut archive = ZipArchive::new(reader)?;
+    let (entries, elements) = match format {
+        InputFormat::Docx => (vec!["word/document.xml".to_string()], &DOCX),
+        InputFormat::Odt => (vec!["content.xml".to_string()], &ODT),
+        _ => (epub_spine(&mut archive)?, &EPUB),
+    };
+
+    let mut decoded = Decoded {
+        encoding:       UTF_8,
+        replaced_bytes: 0,
+    };
+    let mut has_text = false;
+    for name in entries {
+        let entry = archive
+            .by_name(&name)
+            .map_err(|error| invalid(format!("{}: {}", name, error)))?;
+        let mut markup = Markup::new(elements);
+        let mut text = String::new();
+        // Separates the first text of the entry from the text of the previous entries
+        let mut separate = has_text;
+        let mut push = |text: &mut String| {
+            if !text.is_empty() {
+                if std::mem::take(&mut separate) {
+                    counter.push("\n\n");
+                }
+                counter.push(text);
+                text.clear();
+                has_text = true;
+            }
+        };
+        let entry_decoded = decode(entry, None, true, args.lossy, |content| {
+            markup.push(content, &mut text);
+            push(&mut text);
+        })?;
+        markup.finish(&mut text);
+        push(&mut text);
+        decoded.encoding = entry_decoded.encoding;
+        decoded.replaced_bytes += entry_decoded.replaced_bytes;
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Cursor, Write},
+        sync::Arc,
+    };
+
+    use zip::{write::FileOptions, ZipWriter};
+
+    use super::*;
+    use crate::shared::{Analysis, Interner};
+
+    fn zip(entries: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let mut archive = writer.finish().unwrap();
+        archive.set_position(0);
+        archive
+    }
+
+    fn analyze(format: InputFormat, entries: &[(&str, &str)]) -> Analysis {
+        let args = Arc::new(Args::default());
+        let mut counter = Counter::new(None, args.clone(), Interner::new(), None);
+        read_document(zip(entries), format, &args, &mut counter).unwrap();
+        counter.finish()
+    }
+
+    fn words(analysis: &Analysis) -> Vec<String> {
+        let mut words =
+            analysis.word_freq.iter().map(|(_, word)| word.to_string()).collect::<Vec<_>>();
+        words.sort();
+        words
+    }
+
+    const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container" version="1.0">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+    const PACKAGE: &str = r#"<?xml version="1.0"?>
+<opf:package xmlns:opf="http://www.idpf.org/2007/opf" version="3.0">
+  <opf:manifest>
+    <opf:item id="nav" href="nav.xhtml" properties="nav"/>
+    <opf:item href="Text/Chapter%20One.xhtml" id="one" media-type="application/xhtml+xml"/>
+    <opf:item id='two' href='../Two &amp; Three.xhtml'/>
+  </opf:manifest>
+  <opf:spine>
+    <opf:itemref idref="two"/>
+    <opf:itemref idref="one"/>
+    <opf:itemref idref="missing"/>
+  </opf:spine>
+</opf:package>"#;
+
+    fn chapter(title: &str, body: &str) -> String {
+        format!("<html><head><title>{}</title></head><body>{}</body></html>", title, body)
+    }
+
+    #[test]
+    fn reads_attributes_and_paths() {
+        let tag = r#"item xid="no" id = 'yes' href="a b.xhtml""#;
+        assert_eq!(attribute(tag, "id"), Some("yes"));
+        assert_eq!(attribute(tag, "href"), Some("a b.xhtml"));
+        assert_eq!(attribute(tag, "media-type"), None);
+        assert_eq!(attribute("item id=unquoted", "id"), None);
+
+        assert_eq!(decode_href("Chapter%201%20%C3%A9.xhtml"), "Chapter 1 é.xhtml");
+        assert_eq!(decode_href("a&amp;b%2"), "a&b%2");
+        assert_eq!(decode_href("100%zz"), "100%zz");
+
+        assert_eq!(resolve("OEBPS", "../Text/one.xhtml#start"), "Text/one.xhtml");
+        assert_eq!(resolve("OEBPS/Text", "./one.xhtml"), "OEBPS/Text/one.xhtml");
+        assert_eq!(resolve("", "one.xhtml"), "one.xhtml");
+    }
+
+    #[test]
+    fn reads_epub_spine_in_order() {
+        let mut archive = ZipArchive::new(zip(&[
+            ("META-INF/container.xml", CONTAINER),
+            ("OEBPS/content.opf", PACKAGE),
+        ]))
+        .unwrap();
+        assert_eq!(epub_spine(&mut archive).unwrap(), [
+            "Two & Three.xhtml",
+            "OEBPS/Text/Chapter One.xhtml"
+        ]);
+
+        let mut archive =
+            ZipArchive::new(zip(&[("META-INF/container.xml", "<container/>")])).unwrap();
+        assert_eq!(epub_spine(&mut archive).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn reads_epub_chapters() {
+        let one = chapter("Title", "<h1>First chapter</h1><script>skipped()</script>");
+        let two = chapter("Title", "<p>Second&nbsp;chapter</p>");
+        let analysis = analyze(InputFormat::Epub, &[
+            ("META-INF/container.xml", CONTAINER),
+            ("OEBPS/content.opf", PACKAGE),
+            ("OEBPS/Text/Chapter One.xhtml", &one),
+            ("Two & Three.xhtml", &two),
+        ]);
+        assert_eq!(words(&analysis), ["First", "Second", "chapter"]);
+        assert_eq!(analysis.para_count, 2);
+
+        let error = {
+            let args = Arc::new(Args::default());
+            let mut counter = Counter::new(None, args.clone(), Interner::new(), None);
+            let archive = zip(&[
+                ("META-INF/container.xml", CONTAINER),
+                ("OEBPS/content.opf", PACKAGE),
+            ]);
+            read_document(archive, InputFormat::Epub, &args, &mut counter).err().unwrap()
+        };
+        assert!(error.to_string().starts_with("Two & Three.xhtml: "));
+    }
+
+    #[test]
+    fn reads_docx_body_without_deleted_text() {
+        let document = r#"<w:document><w:body>
+            <w:p><w:r><w:t>Kept</w:t></w:r><w:del><w:r><w:delText>Deleted</w:delText></w:r></w:del>
+            <w:r><w:t xml:space="preserve"> text</w:t></w:r>
+            <w:r><w:instrText>PAGE</w:instrText></w:r></w:p>
+            <w:p><w:r><w:t>Split</w:t><w:t>word</w:t><w:tab/><w:t>after</w:t></w:r>
+            <w:r><w:br/><w:t>tab</w:t></w:r></w:p>
+        </w:body></w:document>"#;
+        let analysis = analyze(InputFormat::Docx, &[("word/document.xml", document)]);
+        assert_eq!(words(&analysis), ["Kept", "Splitword", "after", "tab", "text"]);
+        assert_eq!(analysis.para_count, 2);
+    }
+
+    #[test]
+    fn reads_odt_body_without_annotations() {
+        let content = r#"<office:document-content><office:body><office:text>
+            <text:h text:outline-level="1">Title</text:h>
+            <text:p>One<text:s/>two<office:annotation>
+                <text:p>Comment</text:p>
+            </office:annotation></text:p>
+            <text:p>Three<text:line-break/>four</text:p>
+        </office:text></office:body></office:document-content>"#;
+        let analysis = analyze(InputFormat::Odt, &[("content.xml", content)]);
+        assert_eq!(words(&analysis), ["One", "Three", "Title", "four", "two"]);
+        assert_eq!(analysis.para_count, 3);
+    }
+}
@@ -0,0 +1,284 @@
+use std::io::{Result, Write};
+
+use csv::WriterBuilder;
+use regex::Regex;
+use serde::Serialize;
+
+use super::{Analysis, UniqueString};
+
+#[derive(Serialize)]
+struct WordRecord<'a> {
+    word:  &'a str,
+    count: usize,
+    /// Surface forms counted as the word if it is a stem or lemma
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    forms: Vec<String>,
+}
+
+fn records<'a>(
+    frequencies: &'a [(usize, UniqueString)], forms: Option<&Analysis>, filter: Option<&Regex>,
+) -> Vec<WordRecord<'a>> {
+    frequencies
+        .iter()
+        .filter(|(_, word)| filter.map_or(true, |filter| filter.is_match(word)))
+        .map(|(count, word)| WordRecord {
+            word:  word.as_str(),
+            count: *count,
+            forms: forms
+                .map(|analysis| analysis.word_forms(word).into_iter().map(Into::into).collect())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+struct AnalysisRecord<'a> {
+    file:                  Option<String>,
+    encoding:              Option<&'static str>,
+    replaced_bytes:        usize,
+    skipped_binary:        usize,
+    word_count:            usize,
+    char_count:            usize,
+    sent_count:            usize,
+    para_count:            usize,
+    word_uniqs:            usize,
+    word_dist_mean:        f64,
+    word_dist_stddev:      f64,
+    word_dist_median:      f64,
+    word_dist_mode:        f64,
+    stopword_count:        usize,
+    stopword_uniqs:        usize,
+    all_word_count:        usize,
+    all_word_uniqs:        usize,
+    all_word_dist_mean:    f64,
+    all_word_dist_stddev:  f64,
+    all_word_dist_median:  f64,
+    all_word_dist_mode:    f64,
+    syllable_count:        usize,
+    polysyllable_count:    usize,
+    letter_count:          usize,
+    flesch_reading_ease:   f64,
+    flesch_kincaid_grade:  f64,
+    gunning_fog:           f64,
+    smog:                  f64,
+    coleman_liau:          f64,
+    automated_readability: f64,
+    cue_duration:          f64,
+    words_per_minute:      f64,
+    words:                 Vec<WordRecord<'a>>,
+    ngram_count:           usize,
+    ngrams:                Vec<WordRecord<'a>>,
+}
+impl<'a> AnalysisRecord<'a> {
+    fn new(analysis: &'a Analysis, filter: Option<&Regex>) -> Self {
+        Self {
+            file:                  analysis.file.as_ref().map(|file| file.display().to_string()),
+            encoding:              analysis.encoding.map(|encoding| encoding.name()),
+            replaced_bytes:        analysis.replaced_bytes,
+            skipped_binary:        analysis.skipped_binary,
+            word_count:            analysis.word_count,
+            char_count:            analysis.char_count,
+            sent_count:            analysis.sent_count,
+            para_count:            analysis.para_count,
+            word_uniqs:            analysis.word_uniqs,
+            word_dist_mean:        analysis.word_dist_mean,
+            word_dist_stddev:      analysis.word_dist_stddev,
+            word_dist_median:      analysis.word_dist_median,
+            word_dist_mode:        analysis.word_dist_mode,
+            stopword_count:        analysis.stopword_count,
+            stopword_uniqs:        analysis.stopword_uniqs,
+            all_word_count:        analysis.all_word_count,
+            all_word_uniqs:        analysis.all_word_uniqs,
+            all_word_dist_mean:    analysis.all_word_dist_mean,
+            all_word_dist_stddev:  analysis.all_word_dist_stddev,
+            all_word_dist_median:  analysis.all_word_dist_median,
+            all_word_dist_mode:    analysis.all_word_dist_mode,
+            syllable_count:        analysis.syllable_count,
+            polysyllable_count:    analysis.polysyllable_count,
+            letter_count:          analysis.letter_count,
+            flesch_reading_ease:   analysis.flesch_reading_ease,
+            flesch_kincaid_grade:  analysis.flesch_kincaid_grade,
+            gunning_fog:           analysis.gunning_fog,
+            smog:                  analysis.smog,
+            coleman_liau:          analysis.coleman_liau,
+            automated_readability: analysis.automated_readability,
+            cue_duration:          analysis.cue_duration,
+            words_per_minute:      analysis.words_per_minute,
+            words:                 records(&analysis.word_freq, Some(analysis), filter),
+            ngram_count:           analysis.ngram_count,
+            ngrams:                records(&analysis.ngram_freq, None, filter),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ReportRecord<'a> {
+    files:   Vec<AnalysisRecord<'a>>,
+    summary: Option<AnalysisRecord<'a>>,
+}
+
+/// Writes the per-file analyses and the combined analysis as a JSON document.
+///
+/// The document is an object with a `files` array and a `summary` object that is `null` if there
+/// is no combined analysis. Word and n-gram lists contain all entries matching `filter`, sorted by
+/// descending frequency, words counted as stems or lemmas list the `forms` that were counted as
+/// them.
+pub fn write_json<W: Write>(
+    mut writer: W, analyses: &[Analysis], total: Option<&Analysis>, filter: Option<&Regex>,
+) -> Result<()> {
+    let report = ReportRecord {
+        files:   analyses.iter().map(|analysis| AnalysisRecord::new(analysis, filter)).collect(),
+        summary: total.map(|analysis| AnalysisRecord::new(analysis, filter)),
+    };
+    serde_json::to_writer_pretty(&mut writer, &report)?;
+    writeln!(writer)
+}
+
+#[derive(Serialize)]
+struct FrequencyRecord<'a> {
+    file:      &'a str,
+    word:      &'a str,
+    count:     usize,
+    frequency: f64,
+    rank:      usize,
+}
+
+fn write_table<W: Write>(
+    writer: W, analyses: &[Analysis], total: Option<&Analysis>, filter: Option<&Regex>,
+    delimiter: u8,
+) -> Result<()> {
+    let mut writer =
+        WriterBuilder::new().delimiter(delimiter).has_headers(false).from_writer(writer);
+    // The header is written explicitly so that it is also present if there are no rows
+    writer.write_record(["file", "word", "count", "frequency", "rank"])?;
+    for (file, analysis) in analyses
+        .iter()
+        .map(|analysis| {
+            let file = analysis.file.as_ref().map(|file| file.display().to_string());
+            (file.unwrap_or_else(|| "-".to_owned()), analysis)
+        })
+        .chain(total.map(|analysis| ("<total>".to_owned(), analysis)))
+    {
+        let mut rank = 0;
+        let mut last_count = 0;
+        for (i, (count, word)) in analysis.word_freq.iter().enumerate() {
+            if *count != last_count {
+                rank = i + 1;
+                last_count = *count;
+            }
+            if filter.is_some_and(|filter| !filter.is_match(word)) {
+                continue;
+            }
+            writer.serialize(FrequencyRecord {
+                file: &file,
+                word: word.as_str(),
+                count: *count,
+                frequency: *count as f64 / analysis.word_count as f64,
+                rank,
+            })?;
+        }
+    }
+    writer.flush()
+}
+
+/// Writes the word frequencies of the per-file analyses and the combined analysis as CSV.
+///
+/// Every row contains the file, word, count, frequency relative to the word count of the file
+/// and the rank of the word, with words of the same count sharing a rank. Rows of the combined
+/// analysis have `<total>` as file and rows of in-memory content `-`, only words matching
+/// `filter` are written. The header is written even if there are no rows.
+pub fn write_csv<W: Write>(
+    writer: W, analyses: &[Analysis], total: Option<&Analysis>, filter: Option<&Regex>,
+) -> Result<()> {
+    write_table(writer, analyses, total, filter, b',')
+}
+
+/// Writes the word frequencies of the per-file analyses and the combined analysis as TSV.
+///
+/// The columns are the same as in [`write_csv`].
+pub fn write_tsv<W: Write>(
+    writer: W, analyses: &[Analysis], total: Option<&Analysis>, filter: Option<&Regex>,
+) -> Result<()> {
+    write_table(writer, analyses, total, filter, b'\t')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::Interner;
+
+    fn csv(analyses: &[Analysis], total: Option<&Analysis>) -> String {
+        let mut output = Vec::new();
+        write_csv(&mut output, analyses, total, None).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn json(analyses: &[Analysis], total: Option<&Analysis>, filter: &str) -> serde_json::Value {
+        let mut output = Vec::new();
+        write_json(&mut output, analyses, total, Some(&Regex::new(filter).unwrap())).unwrap();
+        serde_json::from_slice(&output).unwrap()
+    }
+
+    #[test]
+    fn writes_json_report() {
+        let interner = Interner::new();
+        let walk = interner.intern("walk");
+        let analysis = Analysis {
+            file: Some("book.txt".into()),
+            word_count: 4,
+            word_freq: vec![(3, walk.clone()), (1, interner.intern("cat"))],
+            ngram_count: 3,
+            ngram_freq: vec![
+                (2, interner.intern("walk walk")),
+                (1, interner.intern("walk cat")),
+            ],
+            ..Default::default()
+        };
+        analysis.word_forms_map.insert(
+            walk,
+            ["walked", "walking"].into_iter().map(|form| interner.intern(form)).collect(),
+        );
+
+        let report = json(std::slice::from_ref(&analysis), None, "walk");
+        assert!(report["summary"].is_null());
+        let file = &report["files"][0];
+        assert_eq!(file["file"], "book.txt");
+        assert_eq!(file["word_count"], 4);
+        assert_eq!(
+            file["words"],
+            serde_json::json!([{"word": "walk", "count": 3, "forms": ["walked", "walking"]}])
+        );
+        assert_eq!(file["ngram_count"], 3);
+        assert_eq!(
+            file["ngrams"],
+            serde_json::json!([{"word": "walk walk", "count": 2}, {"word": "walk cat", "count": 1}])
+        );
+
+        let report = json(&[], Some(&analysis), "cat");
+        assert_eq!(report["files"], serde_json::json!([]));
+        assert_eq!(report["summary"]["words"], serde_json::json!([{"word": "cat", "count": 1}]));
+        assert_eq!(
+            report["summary"]["ngrams"],
+            serde_json::json!([{"word": "walk cat", "count": 1}])
+        );
+    }
+
+    #[test]
+    fn writes_header_without_rows() {
+        assert_eq!(csv(&[], None), "file,word,count,frequency,rank\n");
+    }
+
+    #[test]
+    fn labels_content_and_total() {
+        let interner = Interner::new();
+        let analysis = Analysis {
+            word_count: 2,
+            word_freq: vec![(2, interner.intern("word"))],
+            ..Default::default()
+        };
+        assert_eq!(
+            csv(std::slice::from_ref(&analysis), Some(&analysis)),
+            "file,word,count,frequency,rank\n-,word,2,1.0,1\n<total>,word,2,1.0,1\n"
+        );
+    }
+}
@@ -0,0 +1,590 @@
+use std::path::Path;
+
+use super::Extractor;
+
+/// Part of source code that is analyzed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CodeMode {
+    /// Comments, doc comments and docstrings
+    Comments,
+    /// String literals
+    Strings,
+    /// Identifiers that are not keywords
+    Identifiers,
+}
+impl CodeMode {
+    /// Names of the modes accepted by [`Self::from_name`].
+    pub const NAMES: &'static [&'static str] = &["comments", "strings", "identifiers"];
+
+    /// Returns the mode with a name in [`Self::NAMES`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "comments" => Some(Self::Comments),
+            "strings" => Some(Self::Strings),
+            "identifiers" => Some(Self::Identifiers),
+            _ => None,
+        }
+    }
+}
+
+/// Lexical syntax of a family of programming languages.
+struct Syntax {
+    /// Marker of comments that end at the end of the line
+    line_comment:  &'static str,
+    /// Whether line comments only start at the start of a word
+    word_comment:  bool,
+    /// Markers of the start and end of block comments
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Whether block comments can be nested
+    nested:        bool,
+    /// Characters that delimit string literals
+    quotes:        &'static str,
+    /// Quotes of string literals that can span lines
+    multiline:     &'static str,
+    /// Quotes of string literals without escape sequences
+    raw:           &'static str,
+    /// Whether `'` delimits character literals, and lifetimes in Rust
+    chars:         bool,
+    /// Whether tripled quotes delimit strings that can span lines, docstrings at the line start
+    triple_quotes: bool,
+    /// Identifiers that prefix string literals, with `r` making them raw in Rust
+    prefixes:      &'static [&'static str],
+    /// Keywords that are not counted as identifiers
+    keywords:      &'static [&'static str],
+}
+
+const RUST: Syntax = Syntax {
+    line_comment:  "//",
+    word_comment:  false,
+    block_comment: Some(("/*", "*/")),
+    nested:        true,
+    quotes:        "\"",
+    multiline:     "\"",
+    raw:           "",
+    chars:         true,
+    triple_quotes: false,
+    prefixes:      &["b", "br", "c", "cr", "r"],
+    keywords:      &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while",
+    ],
+};
+
+const PYTHON: Syntax = Syntax {
+    line_comment:  "#",
+    word_comment:  false,
+    block_comment: None,
+    nested:        false,
+    quotes:        "\"'",
+    multiline:     "",
+    raw:           "",
+    chars:         false,
+    triple_quotes: true,
+    prefixes:      &["b", "br", "f", "fr", "r", "rb", "rf", "u"],
+    keywords:      &[
+        "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+        "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+        "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+        "self", "try", "while", "with", "yield",
+    ],
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    line_comment:  "//",
+    word_comment:  false,
+    block_comment: Some(("/*", "*/")),
+    nested:        false,
+    quotes:        "\"'`",
+    multiline:     "`",
+    raw:           "",
+    chars:         false,
+    triple_quotes: false,
+    prefixes:      &[],
+    keywords:      &[
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "debugger",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "if",
+        "import",
+        "in",
+        "instanceof",
+        "let",
+        "new",
+        "null",
+        "of",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typeof",
+        "undefined",
+        "var",
+        "void",
+        "while",
+        "with",
+        "yield",
+    ],
+};
+
+const C_FAMILY: Syntax = Syntax {
+    line_comment:  "//",
+    word_comment:  false,
+    block_comment: Some(("/*", "*/")),
+    nested:        false,
+    quotes:        "\"`",
+    multiline:     "`",
+    raw:           "`",
+    chars:         true,
+    triple_quotes: false,
+    prefixes:      &[],
+    keywords:      &[
+        "abstract",
+        "auto",
+        "bool",
+        "boolean",
+        "break",
+        "byte",
+        "case",
+        "catch",
+        "char",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "delete",
+        "do",
+        "double",
+        "else",
+        "enum",
+        "extends",
+        "extern",
+        "false",
+        "final",
+        "float",
+        "for",
+        "func",
+        "goto",
+        "if",
+        "implements",
+        "import",
+        "include",
+        "int",
+        "interface",
+        "long",
+        "namespace",
+        "new",
+        "null",
+        "nullptr",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "register",
+        "return",
+        "short",
+        "signed",
+        "sizeof",
+        "static",
+        "struct",
+        "switch",
+        "template",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typedef",
+        "typename",
+        "union",
+        "unsigned",
+        "using",
+        "var",
+        "virtual",
+        "void",
+        "volatile",
+        "while",
+    ],
+};
+
+const SHELL: Syntax = Syntax {
+    line_comment:  "#",
+    word_comment:  true,
+    block_comment: None,
+    nested:        false,
+    quotes:        "\"'",
+    multiline:     "\"'",
+    raw:           "'",
+    chars:         false,
+    triple_quotes: false,
+    prefixes:      &[],
+    keywords:      &[
+        "case", "declare", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function",
+        "if", "in", "local", "readonly", "return", "select", "then", "time", "until", "while",
+    ],
+};
+
+/// Programming language of a source file, which determines how it is lexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    CFamily,
+    Shell,
+}
+impl Language {
+    /// Returns the language of a source file by its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension().and_then(|extension| extension.to_str())?;
+        match extension.to_ascii_lowercase().as_str() {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" | "pyw" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Some(Self::JavaScript),
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" | "cs" | "java" | "kt"
+            | "kts" | "go" | "swift" | "scala" | "dart" => Some(Self::CFamily),
+            "sh" | "bash" | "zsh" | "ksh" => Some(Self::Shell),
+            _ => None,
+        }
+    }
+
+    fn syntax(self) -> &'static Syntax {
+        match self {
+            Self::Rust => &RUST,
+            Self::Python => &PYTHON,
+            Self::JavaScript => &JAVASCRIPT,
+            Self::CFamily => &C_FAMILY,
+            Self::Shell => &SHELL,
+        }
+    }
+}
+
+/// Returns the content of a string literal starting after its opening delimiter and the length
+/// including the closing delimiter.
+///
+/// Escape sequences of letters are replaced with spaces, others with the escaped character.
+/// Literals that can't span lines end before the line break if they are not closed.
+fn scan_string(rest: &str, close: &str, escapes: bool, multiline: bool) -> (String, usize) {
+    let mut content = String::new();
+    let mut chars = rest.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if rest[index..].starts_with(close) {
+            return (content, index + close.len());
+        }
+        match c {
+            '\\' if escapes => match chars.next() {
+                Some((_, '\n')) => (),
+                Some((_, escaped)) if escaped.is_alphabetic() => content.push(' '),
+                Some((_, escaped)) => content.push(escaped),
+                None => (),
+            },
+            '\n' if !multiline => return (content, index),
+            c => content.push(c),
+        }
+    }
+    (content, rest.len())
+}
+
+/// Returns the length of a character literal at the start of the text, `None` if the quote starts
+/// a lifetime or label.
+fn char_literal(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(1);
+    match chars.next()? {
+        (_, '\\') => {
+            // The escaped character can be a quote
+            let end = rest
+                .get(3..)
+                .and_then(|rest| rest.find(['\'', '\n']))
+                .map_or(rest.len(), |end| end + 3);
+            Some((end + 1).min(rest.len()))
+        }
+        (_, '\n') => None,
+        (_, _) => match chars.next()? {
+            (index, '\'') => Some(index + 1),
+            _ => None,
+        },
+    }
+}
+
+/// Extractor of the comments, string literals or identifiers of source code.
+///
+/// The lines of comments and strings are kept as lines, blank comment lines and lines of code
+/// without any of the extracted text separate paragraphs. Identifiers are extracted as a line per
+/// line of code. The content is lexed as a whole when it is finished.
+pub struct Code {
+    syntax:        &'static Syntax,
+    mode:          CodeMode,
+    content:       String,
+    /// Whether text was extracted from the current line of code
+    line_has_text: bool,
+    /// Whether the current line of code has code before the current position
+    line_has_code: bool,
+}
+impl Code {
+    pub fn new(language: Language, mode: CodeMode) -> Self {
+        Self {
+            syntax: language.syntax(),
+            mode,
+            content: String::new(),
+            line_has_text: false,
+            line_has_code: false,
+        }
+    }
+
+    fn paragraph_break(output: &mut String) {
+        if !output.is_empty() && !output.ends_with("\n\n") {
+            output.push('\n');
+        }
+    }
+
+    fn end_line(&mut self, output: &mut String) {
+        match self.line_has_text {
+            true if self.mode == CodeMode::Identifiers => output.push('\n'),
+            true => (),
+            false => Self::paragraph_break(output),
+        }
+        self.line_has_text = false;
+        self.line_has_code = false;
+    }
+
+    /// Appends the lines of a comment or string to the output if they are extracted.
+    fn span(&mut self, kind: CodeMode, text: &str, output: &mut String) {
+        if kind == CodeMode::Strings {
+            self.line_has_code = true;
+        }
+        if kind != self.mode {
+            return;
+        }
+        for line in text.lines() {
+            let mut line = line.trim();
+            if kind == CodeMode::Comments {
+                line = line.strip_prefix('*').unwrap_or(line).trim();
+            }
+            if line.is_empty() {
+                Self::paragraph_break(output);
+            } else {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+        self.line_has_text = true;
+    }
+
+    /// Returns the length of a block comment at the start of the text and its content.
+    fn block_comment<'a>(&self, rest: &'a str, open: &str, close: &str) -> (&'a str, usize) {
+        let mut depth = 0;
+        let mut index = 0;
+        while index < rest.len() {
+            if rest[index..].starts_with(open) && (depth == 0 || self.syntax.nested) {
+                depth += 1;
+                index += open.len();
+            } else if rest[index..].starts_with(close) {
+                depth -= 1;
+                index += close.len();
+                if depth == 0 {
+                    return (&rest[open.len()..index - close.len()], index);
+                }
+            } else {
+                index += rest[index..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        (&rest[open.len()..], rest.len())
+    }
+
+    fn lex(&mut self, text: &str, output: &mut String) {
+        let syntax = self.syntax;
+        let mut index = 0;
+        if text.starts_with("#!") {
+            index = text.find('\n').unwrap_or(text.len());
+        }
+        while let Some(c) = text[index..].chars().next() {
+            let rest = &text[index..];
+            let after_space = text[..index].ends_with(|c: char| c.is_whitespace() || c == ';');
+            if c == '\n' {
+                self.end_line(output);
+                index += 1;
+            } else if rest.starts_with(syntax.line_comment)
+                && (!syntax.word_comment || index == 0 || after_space)
+            {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                let comment =
+                    rest[syntax.line_comment.len()..end].trim_start_matches(['/', '!', '#']);
+                self.span(CodeMode::Comments, comment, output);
+                index += end;
+            } else if let Some((open, close)) =
+                syntax.block_comment.filter(|(open, _)| rest.starts_with(open))
+            {
+                let (comment, length) = self.block_comment(rest, open, close);
+                self.span(CodeMode::Comments, comment.trim_start_matches(['*', '!']), output);
+                index += length;
+            } else if syntax.triple_quotes
+                && (rest.starts_with("\"\"\"") || rest.starts_with("'''"))
+            {
+                let (content, length) = scan_string(&rest[3..], &rest[..3], true, true);
+                // Strings at the start of a line are docstrings
+                let kind = match self.line_has_code {
+                    true => CodeMode::Strings,
+                    false => CodeMode::Comments,
+                };
+                self.span(kind, &content, output);
+                index += length + 3;
+            } else if syntax.quotes.contains(c) {
+                let (content, length) = scan_string(
+                    &rest[1..],
+                    &rest[..1],
+                    !syntax.raw.contains(c),
+                    syntax.multiline.contains(c),
+                );
+                self.span(CodeMode::Strings, &content, output);
+                index += length + 1;
+            } else if c == '\'' && syntax.chars {
+                self.line_has_code = true;
+                index += char_literal(rest).unwrap_or(1);
+            } else if c.is_ascii_digit() {
+                self.line_has_code = true;
+                index += rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                    .unwrap_or(rest.len());
+            } else if c.is_alphabetic() || c == '_' {
+                let length =
+                    rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+                let identifier = &rest[..length];
+                index += length;
+                let next = &text[index..];
+                let prefix =
+                    syntax.prefixes.iter().any(|prefix| prefix.eq_ignore_ascii_case(identifier));
+                if prefix && syntax.chars && identifier.contains('r') {
+                    // Raw strings of Rust, closed by a quote and as many hashes as they're opened with
+                    let hashes = next.len() - next.trim_start_matches('#').len();
+                    if next[hashes..].starts_with('"') {
+                        let close = format!("\"{}", &next[..hashes]);
+                        let (content, length) =
+                            scan_string(&next[hashes + 1..], &close, false, true);
+                        self.span(CodeMode::Strings, &content, output);
+                        index += hashes + 1 + length;
+                        continue;
+                    }
+                }
+                if prefix && next.starts_with(|c: char| syntax.quotes.contains(c) || c == '\'') {
+                    continue;
+                }
+                self.line_has_code = true;
+                if self.mode == CodeMode::Identifiers && !syntax.keywords.contains(&identifier) {
+                    output.push_str(identifier);
+                    output.push(' ');
+                    self.line_has_text = true;
+                }
+            } else {
+                self.line_has_code |= !c.is_whitespace();
+                index += c.len_utf8();
+            }
+        }
+        self.end_line(output);
+    }
+}
+impl Extractor for Code {
+    fn push(&mut self, content: &str, _output: &mut String) {
+        self.content.push_str(content);
+    }
+
+    fn finish(&mut self, output: &mut String) {
+        let content = std::mem::take(&mut self.content);
+        let start = output.len();
+        self.lex(&content, output);
+        // Lines of code at the end don't separate a paragraph
+        let end = start + output[start..].trim_end().len();
+        output.truncate(end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(language: Language, mode: CodeMode, content: &str) -> String {
+        let mut output = String::new();
+        let mut code = Code::new(language, mode);
+        code.push(content, &mut output);
+        code.finish(&mut output);
+        output
+    }
+
+    #[test]
+    fn extracts_rust_raw_strings() {
+        let source = "let a = r#\"a \"quoted\" // not a comment\"#; // real comment\n\
+                      let b = br\"raw \\n bytes\"; let c = r##\"one\"# two\"##;\n";
+        assert_eq!(
+            extract(Language::Rust, CodeMode::Strings, source),
+            "a \"quoted\" // not a comment\nraw \\n bytes\none\"# two"
+        );
+        assert_eq!(extract(Language::Rust, CodeMode::Comments, source), "real comment");
+        assert_eq!(extract(Language::Rust, CodeMode::Identifiers, source), "a \nb c");
+    }
+
+    #[test]
+    fn extracts_nested_block_comments() {
+        let source = "/* outer /* inner */ still comment */ code(); /** two */\n";
+        assert_eq!(
+            extract(Language::Rust, CodeMode::Comments, source),
+            "outer /* inner */ still comment\ntwo"
+        );
+        assert_eq!(extract(Language::CFamily, CodeMode::Comments, source), "outer /* inner\ntwo");
+        assert_eq!(
+            extract(Language::Rust, CodeMode::Comments, "/*\n * Line one\n *\n * Line two\n */"),
+            "Line one\n\nLine two"
+        );
+    }
+
+    #[test]
+    fn separates_python_docstrings_and_comments() {
+        let source = "\"\"\"Module docstring.\"\"\"\ndef f(x):\n    \"\"\"Function docstring\n    \
+                      over lines.\"\"\"\n    y = '''not a docstring'''  # trailing comment\n    \
+                      return \"# not a comment\"\n";
+        assert_eq!(
+            extract(Language::Python, CodeMode::Comments, source),
+            "Module docstring.\n\nFunction docstring\nover lines.\ntrailing comment"
+        );
+        assert_eq!(
+            extract(Language::Python, CodeMode::Strings, source),
+            "not a docstring\n# not a comment"
+        );
+    }
+
+    #[test]
+    fn keeps_escaped_quotes_in_strings() {
+        let source = "const s = \"say \\\"hi\\\"\\tnow\"; const t = 'it\\'s'; // it's \"quoted\"\n";
+        assert_eq!(
+            extract(Language::JavaScript, CodeMode::Strings, source),
+            "say \"hi\" now\nit's"
+        );
+        assert_eq!(extract(Language::JavaScript, CodeMode::Comments, source), "it's \"quoted\"");
+
+        let source =
+            "fn f<'a>(x: &'a str) -> char { let q = '\"'; let e = '\\''; \"a\\\\\" } // c\n";
+        assert_eq!(extract(Language::Rust, CodeMode::Strings, source), "a\\");
+        assert_eq!(extract(Language::Rust, CodeMode::Comments, source), "c");
+    }
+}
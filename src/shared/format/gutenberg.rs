@@ -0,0 +1,185 @@
+use super::Extractor;
+
+/// Size of the text before a start marker after which the content is assumed to have no markers.
+const MAX_HEADER_SIZE: usize = 1 << 20;
+
+/// Returns whether a line is a `*** START OF ... ***` or `*** END OF ... ***` marker.
+fn is_marker(line: &str, kind: &str) -> bool {
+    let Some(marker) = line.trim().strip_prefix("***") else {
+        return false;
+    };
+    let marker = marker.trim_start().as_bytes();
+    marker.len() >= kind.len() && marker[..kind.len()].eq_ignore_ascii_case(kind.as_bytes())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    /// Before the start marker, the content is held back until it is found
+    Header,
+    /// Between the markers or in content without a start marker
+    Body,
+    /// After the end marker
+    Footer,
+}
+
+/// Extractor of the text between the `*** START OF` and `*** END OF` markers of Project Gutenberg
+/// e-books, which passes it on to the extractor of the format.
+///
+/// Content without a start marker within its first MiB is passed on as a whole.
+pub struct Gutenberg {
+    inner:   Option<Box<dyn Extractor>>,
+    section: Section,
+    /// Content of the header, or the incomplete line of the last pushed content in the body
+    pending: String,
+}
+impl Gutenberg {
+    pub fn new(inner: Option<Box<dyn Extractor>>) -> Self {
+        Self {
+            inner,
+            section: Section::Header,
+            pending: String::new(),
+        }
+    }
+
+    fn pass(&mut self, content: &str, output: &mut String) {
+        match &mut self.inner {
+            Some(inner) => inner.push(content, output),
+            None => output.push_str(content),
+        }
+    }
+
+    /// Passes on the complete lines of the body up to the end marker.
+    fn body(&mut self, content: &str, output: &mut String) {
+        let complete = content.rfind('\n').map_or(0, |index| index + 1);
+        let mut position = 0;
+        for line in content[..complete].split_inclusive('\n') {
+            if is_marker(line, "END OF") {
+                self.pass(&content[..position], output);
+                self.section = Section::Footer;
+                return;
+            }
+            position += line.len();
+        }
+        self.pass(&content[..complete], output);
+        self.pending.push_str(&content[complete..]);
+    }
+}
+impl Extractor for Gutenberg {
+    fn push(&mut self, content: &str, output: &mut String) {
+        match self.section {
+            Section::Header => {
+                let offset = self.pending.rfind('\n').map_or(0, |index| index + 1);
+                self.pending.push_str(content);
+                let start = self.pending[offset..]
+                    .split_inclusive('\n')
+                    .scan(offset, |position, line| {
+                        *position += line.len();
+                        Some((*position, line))
+                    })
+                    .take_while(|(end, _)| *end <= MAX_HEADER_SIZE)
+                    .find(|(_, line)| line.ends_with('\n') && is_marker(line, "START OF"));
+                if let Some((end, _)) = start {
+                    let body = self.pending.split_off(end);
+                    self.pending.clear();
+                    self.section = Section::Body;
+                    self.body(&body, output);
+                } else if self.pending.len() > MAX_HEADER_SIZE {
+                    let content = std::mem::take(&mut self.pending);
+                    self.section = Section::Body;
+                    self.body(&content, output);
+                }
+            }
+            Section::Body if self.pending.is_empty() => self.body(content, output),
+            Section::Body => {
+                let content = std::mem::take(&mut self.pending) + content;
+                self.body(&content, output);
+            }
+            Section::Footer => (),
+        }
+    }
+
+    fn finish(&mut self, output: &mut String) {
+        let pending = std::mem::take(&mut self.pending);
+        match self.section {
+            Section::Header => self.pass(&pending, output),
+            Section::Body if !is_marker(&pending, "END OF") => self.pass(&pending, output),
+            _ => (),
+        }
+        if let Some(inner) = &mut self.inner {
+            inner.finish(output);
+        }
+    }
+
+    fn duration(&self) -> f64 {
+        self.inner.as_ref().map_or(0.0, |inner| inner.duration())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::format::Markup;
+
+    /// Extracts the text of the content pushed at once and one character at a time.
+    fn extract(content: &str, markup: bool) -> String {
+        let inner = || markup.then(|| Box::<Markup>::default() as Box<dyn Extractor>);
+        let mut whole = String::new();
+        let mut gutenberg = Gutenberg::new(inner());
+        gutenberg.push(content, &mut whole);
+        gutenberg.finish(&mut whole);
+
+        let mut pieces = String::new();
+        let mut gutenberg = Gutenberg::new(inner());
+        let mut buffer = [0; 4];
+        for c in content.chars() {
+            gutenberg.push(c.encode_utf8(&mut buffer), &mut pieces);
+        }
+        gutenberg.finish(&mut pieces);
+        assert_eq!(whole, pieces);
+        whole
+    }
+
+    #[test]
+    fn extracts_text_between_markers() {
+        let text = extract(
+            "The Project Gutenberg eBook of Title\r\n\r\n*** START OF THE PROJECT GUTENBERG EBOOK \
+             TITLE ***\r\nChapter I.\r\n\r\nText.\r\n*** END OF THE PROJECT GUTENBERG EBOOK TITLE \
+             ***\r\nLicense.\r\n",
+            false,
+        );
+        assert_eq!(text, "Chapter I.\r\n\r\nText.\r\n");
+        let text = extract(
+            "Header\n  ***start of this ebook***\nText\nlast line\n*** end of this ebook ***",
+            false,
+        );
+        assert_eq!(text, "Text\nlast line\n");
+    }
+
+    #[test]
+    fn passes_body_to_inner_extractor() {
+        let text = extract(
+            "<p>Header</p>\n*** START OF THE EBOOK ***\n<p>One &amp;\ntwo</p>\n*** END OF THE \
+             EBOOK ***\n<p>Footer</p>\n",
+            true,
+        );
+        assert_eq!(text, "One & two");
+    }
+
+    #[test]
+    fn passes_content_without_markers() {
+        let content = "Text without *** START OF markers ***\nover two lines";
+        assert_eq!(extract(content, false), content);
+        let text = extract("Header\n*** START OF THE EBOOK ***\nText\nwithout end", false);
+        assert_eq!(text, "Text\nwithout end");
+    }
+
+    #[test]
+    fn passes_content_without_start_marker_in_first_mib() {
+        let header = "Line\n".repeat(MAX_HEADER_SIZE / 5 - 10);
+        let text = extract(&format!("{header}*** START OF THE EBOOK ***\nText\n"), false);
+        assert_eq!(text, "Text\n");
+        let header = header + &"Line\n".repeat(10);
+        let content = format!("{header}*** START OF THE EBOOK ***\nText\n");
+        assert_eq!(extract(&content, false), content);
+    }
+}
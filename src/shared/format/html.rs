@@ -0,0 +1,482 @@
+use super::Extractor;
+
+/// Names of the character entities from `&nbsp;` (U+00A0) to `&yuml;` (U+00FF).
+const LATIN1_ENTITIES: [&str; 96] = [
+    "nbsp", "iexcl", "cent", "pound", "curren", "yen", "brvbar", "sect", "uml", "copy", "ordf",
+    "laquo", "not", "shy", "reg", "macr", "deg", "plusmn", "sup2", "sup3", "acute", "micro",
+    "para", "middot", "cedil", "sup1", "ordm", "raquo", "frac14", "frac12", "frac34", "iquest",
+    "Agrave", "Aacute", "Acirc", "Atilde", "Auml", "Aring", "AElig", "Ccedil", "Egrave", "Eacute",
+    "Ecirc", "Euml", "Igrave", "Iacute", "Icirc", "Iuml", "ETH", "Ntilde", "Ograve", "Oacute",
+    "Ocirc", "Otilde", "Ouml", "times", "Oslash", "Ugrave", "Uacute", "Ucirc", "Uuml", "Yacute",
+    "THORN", "szlig", "agrave", "aacute", "acirc", "atilde", "auml", "aring", "aelig", "ccedil",
+    "egrave", "eacute", "ecirc", "euml", "igrave", "iacute", "icirc", "iuml", "eth", "ntilde",
+    "ograve", "oacute", "ocirc", "otilde", "ouml", "divide", "oslash", "ugrave", "uacute", "ucirc",
+    "uuml", "yacute", "thorn", "yuml",
+];
+
+/// Other common character entities.
+const ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("OElig", 'Œ'),
+    ("oelig", 'œ'),
+    ("Scaron", 'Š'),
+    ("scaron", 'š'),
+    ("Yuml", 'Ÿ'),
+    ("fnof", 'ƒ'),
+    ("ensp", '\u{2002}'),
+    ("emsp", '\u{2003}'),
+    ("thinsp", '\u{2009}'),
+    ("zwnj", '\u{200C}'),
+    ("zwj", '\u{200D}'),
+    ("ndash", '–'),
+    ("mdash", '—'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("sbquo", '‚'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+    ("bdquo", '„'),
+    ("dagger", '†'),
+    ("Dagger", '‡'),
+    ("bull", '•'),
+    ("hellip", '…'),
+    ("permil", '‰'),
+    ("prime", '′'),
+    ("Prime", '″'),
+    ("lsaquo", '‹'),
+    ("rsaquo", '›'),
+    ("euro", '€'),
+    ("trade", '™'),
+];
+
+/// HTML elements whose content is not text.
+const HTML_RAW: &[&str] = &["script", "style"];
+
+/// HTML elements that separate paragraphs.
+const HTML_PARAGRAPH: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "caption",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "title",
+    "ul",
+];
+
+/// HTML elements that separate lines.
+const HTML_LINE: &[&str] = &["br", "dd", "dt", "li", "td", "th", "tr"];
+
+/// HTML elements that are part of the surrounding text.
+const HTML_INLINE: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "cite", "code", "data", "del", "dfn", "em", "font", "i", "ins",
+    "kbd", "mark", "q", "s", "samp", "small", "span", "strong", "sub", "sup", "time", "tt", "u",
+    "var", "wbr",
+];
+
+/// How the elements of a markup language separate their text.
+///
+/// Element names are matched in lowercase and include their namespace prefix.
+#[derive(Debug)]
+pub struct Elements {
+    /// Elements whose content is not text
+    pub raw:       &'static [&'static str],
+    /// Elements that separate paragraphs
+    pub paragraph: &'static [&'static str],
+    /// Elements that separate lines
+    pub line:      &'static [&'static str],
+    /// Elements that separate words
+    pub space:     &'static [&'static str],
+    /// Elements that are part of the surrounding text
+    pub inline:    &'static [&'static str],
+    /// Whether elements that are not listed separate words or are part of the surrounding text
+    pub separate:  bool,
+}
+
+/// Elements of HTML and XML documents.
+pub const HTML: Elements = Elements {
+    raw:       HTML_RAW,
+    paragraph: HTML_PARAGRAPH,
+    line:      HTML_LINE,
+    space:     &[],
+    inline:    HTML_INLINE,
+    separate:  true,
+};
+
+/// Longest entity name that is decoded.
+const MAX_ENTITY_LENGTH: usize = 32;
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code).filter(|&c| c != '\0');
+    }
+    ENTITIES
+        .iter()
+        .find(|(entity, _)| *entity == name)
+        .map(|&(_, c)| c)
+        .or_else(|| {
+            LATIN1_ENTITIES
+                .iter()
+                .position(|&entity| entity == name)
+                .and_then(|index| char::from_u32(0xA0 + index as u32))
+        })
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Separator {
+    #[default]
+    None,
+    Space,
+    Line,
+    Paragraph,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    #[default]
+    Text,
+    /// After `&`
+    Entity,
+    /// After `<` while reading the element name
+    Open,
+    /// Inside a tag after the element name
+    Tag,
+    /// After `<!`
+    Bang,
+    /// Inside `<!-- -->`
+    Comment,
+    /// Inside `<![CDATA[ ]]>`
+    Cdata,
+    /// Inside `<! >` or `<? >`
+    Declaration,
+    /// Inside the content of a raw element
+    Raw,
+}
+
+/// Extractor of the text of HTML and XML content.
+///
+/// Tags, comments, declarations and the content of raw elements such as scripts and styles are
+/// removed, entities are decoded and whitespace is collapsed. Elements separate paragraphs, lines
+/// or words as given by their [`Elements`], for HTML block elements separate paragraphs while list
+/// items, table cells and line breaks separate lines.
+#[derive(Debug)]
+pub struct Markup {
+    elements:     &'static Elements,
+    state:        State,
+    /// Element name, entity name or the start of a comment or CDATA section
+    name:         String,
+    closing:      bool,
+    self_closing: bool,
+    quote:        Option<char>,
+    /// Number of matched characters of the end of a comment, CDATA section or raw element
+    matched:      usize,
+    raw_end:      String,
+    separator:    Separator,
+    has_text:     bool,
+}
+impl Markup {
+    pub fn new(elements: &'static Elements) -> Self {
+        Self {
+            elements,
+            state: State::default(),
+            name: String::new(),
+            closing: false,
+            self_closing: false,
+            quote: None,
+            matched: 0,
+            raw_end: String::new(),
+            separator: Separator::default(),
+            has_text: false,
+        }
+    }
+
+    fn text(&mut self, c: char, output: &mut String) {
+        if c.is_whitespace() {
+            self.separator = self.separator.max(Separator::Space);
+            return;
+        }
+        if self.has_text {
+            self.separate(output);
+        }
+        self.separator = Separator::None;
+        self.has_text = true;
+        output.push(c);
+    }
+
+    /// Appends the separator of the preceding elements to the output, for text between elements
+    /// that is not pushed to the extractor, like the Markdown text around inline HTML.
+    pub(super) fn separate(&mut self, output: &mut String) {
+        match std::mem::take(&mut self.separator) {
+            Separator::None => (),
+            Separator::Space => output.push(' '),
+            Separator::Line => output.push('\n'),
+            Separator::Paragraph => output.push_str("\n\n"),
+        }
+    }
+
+    fn literal(&mut self, prefix: &str, output: &mut String) {
+        for c in prefix.chars() {
+            self.text(c, output);
+        }
+    }
+
+    fn end_tag(&mut self) {
+        let name = std::mem::take(&mut self.name);
+        let is = |names: &[&str]| names.contains(&name.as_str());
+        let elements = self.elements;
+        let separator = if is(elements.inline) {
+            Separator::None
+        } else if is(elements.paragraph) {
+            Separator::Paragraph
+        } else if is(elements.line) {
+            Separator::Line
+        } else if is(elements.space) || elements.separate {
+            Separator::Space
+        } else {
+            Separator::None
+        };
+        self.separator = self.separator.max(separator);
+        self.state = State::Text;
+        if !self.closing && !self.self_closing && is(elements.raw) {
+            self.state = State::Raw;
+            self.raw_end = format!("</{}", name);
+            self.matched = 0;
+        }
+    }
+
+    fn char(&mut self, c: char, output: &mut String) {
+        match self.state {
+            State::Text => match c {
+                '<' => {
+                    self.state = State::Open;
+                    self.closing = false;
+                    self.name.clear();
+                }
+                '&' => {
+                    self.state = State::Entity;
+                    self.name.clear();
+                }
+                _ => self.text(c, output),
+            },
+            State::Entity => {
+                if c == ';' {
+                    self.state = State::Text;
+                    match decode_entity(&self.name) {
+                        Some(decoded) => self.text(decoded, output),
+                        None => {
+                            let name = std::mem::take(&mut self.name);
+                            self.literal(&format!("&{};", name), output);
+                        }
+                    }
+                } else if (c.is_ascii_alphanumeric() || c == '#')
+                    && self.name.len() < MAX_ENTITY_LENGTH
+                {
+                    self.name.push(c);
+                } else {
+                    self.state = State::Text;
+                    let name = std::mem::take(&mut self.name);
+                    self.literal(&format!("&{}", name), output);
+                    self.char(c, output);
+                }
+            }
+            State::Open => {
+                if c.is_alphanumeric()
+                    || (!self.name.is_empty() && matches!(c, '-' | '_' | ':' | '.'))
+                {
+                    self.name.extend(c.to_lowercase());
+                } else if self.name.is_empty() && !self.closing && c == '/' {
+                    self.closing = true;
+                } else if self.name.is_empty() && !self.closing && c == '!' {
+                    self.state = State::Bang;
+                } else if self.name.is_empty() && !self.closing && c == '?' {
+                    self.state = State::Declaration;
+                } else if self.name.is_empty() {
+                    self.state = State::Text;
+                    self.literal(if self.closing { "</" } else { "<" }, output);
+                    self.char(c, output);
+                } else {
+                    self.state = State::Tag;
+                    self.quote = None;
+                    self.self_closing = false;
+                    self.char(c, output);
+                }
+            }
+            State::Tag => match (self.quote, c) {
+                (Some(quote), c) if c == quote => self.quote = None,
+                (Some(_), _) => (),
+                (None, '"' | '\'') => self.quote = Some(c),
+                (None, '>') => self.end_tag(),
+                (None, c) => self.self_closing = c == '/',
+            },
+            State::Bang => {
+                self.name.push(c);
+                if self.name == "--" {
+                    self.state = State::Comment;
+                    self.matched = 0;
+                } else if self.name == "[CDATA[" {
+                    self.state = State::Cdata;
+                    self.matched = 0;
+                } else if c == '>' {
+                    self.state = State::Text;
+                } else if !"--".starts_with(&self.name) && !"[CDATA[".starts_with(&self.name) {
+                    self.state = State::Declaration;
+                }
+            }
+            State::Comment => match c {
+                '-' => self.matched += 1,
+                '>' if self.matched >= 2 => {
+                    self.state = State::Text;
+                    self.separator = self.separator.max(Separator::Space);
+                }
+                _ => self.matched = 0,
+            },
+            State::Cdata => match c {
+                ']' if self.matched < 2 => self.matched += 1,
+                ']' => self.text(']', output),
+                '>' if self.matched == 2 => self.state = State::Text,
+                _ => {
+                    for _ in 0..std::mem::take(&mut self.matched) {
+                        self.text(']', output);
+                    }
+                    self.text(c, output);
+                }
+            },
+            State::Declaration => {
+                if c == '>' {
+                    self.state = State::Text;
+                }
+            }
+            State::Raw => {
+                let expected = self.raw_end.as_bytes()[self.matched] as char;
+                if c.to_ascii_lowercase() == expected {
+                    self.matched += 1;
+                    if self.matched == self.raw_end.len() {
+                        self.state = State::Tag;
+                        self.name = self.raw_end[2..].to_string();
+                        self.closing = true;
+                        self.quote = None;
+                        self.self_closing = false;
+                    }
+                } else {
+                    self.matched = (c == '<') as usize;
+                }
+            }
+        }
+    }
+}
+impl Default for Markup {
+    fn default() -> Self {
+        Self::new(&HTML)
+    }
+}
+impl Extractor for Markup {
+    fn push(&mut self, content: &str, output: &mut String) {
+        for c in content.chars() {
+            self.char(c, output);
+        }
+    }
+
+    fn finish(&mut self, output: &mut String) {
+        if self.state == State::Entity {
+            let name = std::mem::take(&mut self.name);
+            self.literal(&format!("&{}", name), output);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Extracts the text of the content pushed at once and one character at a time.
+    fn extract(content: &str) -> String {
+        let mut whole = String::new();
+        let mut markup = Markup::default();
+        markup.push(content, &mut whole);
+        markup.finish(&mut whole);
+
+        let mut pieces = String::new();
+        let mut markup = Markup::default();
+        let mut buffer = [0; 4];
+        for c in content.chars() {
+            markup.push(c.encode_utf8(&mut buffer), &mut pieces);
+        }
+        markup.finish(&mut pieces);
+        assert_eq!(whole, pieces);
+        whole
+    }
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(extract("Caf&eacute; &amp; cr&#232;me &#xE9;t&#XE9;"), "Café & crème été");
+        assert_eq!(extract("&lt;tag&gt; &ldquo;quoted&rdquo;&hellip;"), "<tag> “quoted”…");
+        assert_eq!(extract("&bogus; AT&T &#0; &#xZZ;"), "&bogus; AT&T &#0; &#xZZ;");
+        assert_eq!(extract("Ends with &amp"), "Ends with &amp");
+    }
+
+    #[test]
+    fn removes_raw_elements_comments_and_declarations() {
+        assert_eq!(extract(r#"<script>if (a < b) { x = "</p>"; }</SCRIPT>One"#), "One");
+        assert_eq!(extract("<style type=\"text/css\">p > a { }</style>One"), "One");
+        assert_eq!(extract("One<!-- a -- b -->Two"), "One Two");
+        assert_eq!(extract("<!DOCTYPE html><?xml version=\"1.0\"?>One"), "One");
+        assert_eq!(extract("One<![CDATA[ <b>Two</b> ]x]]>"), "One <b>Two</b> ]x");
+    }
+
+    #[test]
+    fn keeps_text_of_unclosed_tags() {
+        assert_eq!(extract("<p>One<p>Two<br>Three"), "One\n\nTwo\nThree");
+        assert_eq!(extract("a < b and c <= d"), "a < b and c <= d");
+        assert_eq!(extract("One<span title='a > b'>Two"), "OneTwo");
+        assert_eq!(extract("One <div class="), "One");
+    }
+
+    #[test]
+    fn separates_paragraphs_of_block_elements() {
+        assert_eq!(
+            extract(
+                "<h1>Title</h1>\n<p>First <b>bold</b>word.</p>\n<ul><li>One</li><li>Two</li></ul>"
+            ),
+            "Title\n\nFirst boldword.\n\nOne\nTwo"
+        );
+        assert_eq!(
+            extract("<table><tr><td>A</td><td>B</td></tr></table><div>C</div>"),
+            "A\nB\n\nC"
+        );
+        assert_eq!(extract("One<custom>Two</custom><x:y/>Three"), "One Two Three");
+    }
+}
@@ -0,0 +1,198 @@
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag};
+
+use super::{html::Markup, Extractor};
+
+/// Prefixes of words that are removed as URLs.
+const URL_PREFIXES: &[&str] = &["http://", "https://", "ftp://", "mailto:", "www."];
+
+/// Returns the content after YAML (`---`) or TOML (`+++`) front matter.
+fn strip_front_matter(content: &str) -> &str {
+    let mut lines = content.split_inclusive('\n');
+    let delimiter = match lines.next().map(str::trim_end) {
+        Some(delimiter @ ("---" | "+++")) => delimiter,
+        _ => return content,
+    };
+    let mut offset = delimiter.len();
+    for line in content[offset..].split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim_end();
+        if line == delimiter || (delimiter == "---" && line == "...") {
+            return &content[offset..];
+        }
+    }
+    content
+}
+
+fn is_url(word: &str) -> bool {
+    let word = word.trim_start_matches(['(', '<', '[', '"', '\'']);
+    URL_PREFIXES.iter().any(|prefix| {
+        word.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    })
+}
+
+/// Extractor of the prose of Markdown content.
+///
+/// Code blocks, inline code, images, URLs and front matter are removed, and the text of inline
+/// HTML is extracted. Headings, paragraphs, list items and block quotes are separated as
+/// paragraphs, table cells as lines. The content is parsed as a whole when it is finished.
+#[derive(Default)]
+pub struct Markdown {
+    content: String,
+}
+impl Markdown {
+    /// Appends the text of a block without URLs to the output, returns whether it had any text.
+    fn flush(block: &mut String, separator: &mut &'static str, output: &mut String) -> bool {
+        let mut text = String::with_capacity(block.len());
+        for word in block.split_inclusive(char::is_whitespace) {
+            if is_url(word) {
+                text.extend(word.chars().filter(|c| c.is_whitespace()));
+            } else {
+                text.push_str(word);
+            }
+        }
+        block.clear();
+        let text = text.trim();
+        if text.is_empty() {
+            return false;
+        }
+        if !output.is_empty() {
+            output.push_str(separator);
+        }
+        output.push_str(text);
+        *separator = "\n\n";
+        true
+    }
+}
+impl Extractor for Markdown {
+    fn push(&mut self, content: &str, _output: &mut String) {
+        self.content.push_str(content);
+    }
+
+    fn finish(&mut self, output: &mut String) {
+        let content = std::mem::take(&mut self.content);
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_FOOTNOTES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS;
+        let mut html = Markup::default();
+        let mut block = String::new();
+        let mut separator = "\n\n";
+        // Depth of code blocks, images and autolinks whose text is skipped
+        let mut skip = 0usize;
+        for event in Parser::new_ext(strip_front_matter(&content), options) {
+            match event {
+                Event::Start(Tag::CodeBlock(_) | Tag::Image(..))
+                | Event::Start(Tag::Link(LinkType::Autolink | LinkType::Email, ..)) => skip += 1,
+                Event::End(Tag::CodeBlock(_) | Tag::Image(..))
+                | Event::End(Tag::Link(LinkType::Autolink | LinkType::Email, ..)) => {
+                    skip = skip.saturating_sub(1)
+                }
+                Event::End(Tag::TableCell) => {
+                    separator = match Self::flush(&mut block, &mut separator, output) {
+                        true => "\n",
+                        false => separator,
+                    };
+                }
+                Event::End(Tag::Table(_)) => {
+                    Self::flush(&mut block, &mut separator, output);
+                    separator = "\n\n";
+                }
+                Event::Start(
+                    Tag::Paragraph
+                    | Tag::Heading(..)
+                    | Tag::BlockQuote
+                    | Tag::List(_)
+                    | Tag::Item
+                    | Tag::Table(_)
+                    | Tag::TableCell
+                    | Tag::FootnoteDefinition(_),
+                )
+                | Event::End(
+                    Tag::Paragraph
+                    | Tag::Heading(..)
+                    | Tag::BlockQuote
+                    | Tag::List(_)
+                    | Tag::Item
+                    | Tag::FootnoteDefinition(_),
+                )
+                | Event::Rule => {
+                    Self::flush(&mut block, &mut separator, output);
+                }
+                Event::Text(text) if skip == 0 => {
+                    html.separate(&mut block);
+                    block.push_str(&text);
+                }
+                Event::Html(text) => html.push(&text, &mut block),
+                Event::SoftBreak if skip == 0 => block.push(' '),
+                Event::HardBreak if skip == 0 => block.push('\n'),
+                _ => (),
+            }
+        }
+        Self::flush(&mut block, &mut separator, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Extracts the text of the content pushed in two pieces.
+    fn extract(content: &str) -> String {
+        let mut output = String::new();
+        let mut markdown = Markdown::default();
+        let middle = content
+            .char_indices()
+            .nth(content.chars().count() / 2)
+            .map_or(0, |(index, _)| index);
+        markdown.push(&content[..middle], &mut output);
+        markdown.push(&content[middle..], &mut output);
+        markdown.finish(&mut output);
+        output
+    }
+
+    #[test]
+    fn removes_front_matter() {
+        assert_eq!(extract("---\ntitle: Title\ntags: [a, b]\n---\nText."), "Text.");
+        assert_eq!(extract("---\ntitle: Title\n...\nText."), "Text.");
+        assert_eq!(extract("+++\ntitle = \"Title\"\n+++\nText."), "Text.");
+        assert_eq!(extract("---\nNot: front matter"), "Not: front matter");
+        assert_eq!(extract("Text.\n---\ntitle: Title\n---\n"), "Text.\n\ntitle: Title");
+    }
+
+    #[test]
+    fn removes_code() {
+        assert_eq!(
+            extract(
+                "Some `inline code` here.\n\n```rust\nfn main() {}\n```\n\n    indented\n\nEnd."
+            ),
+            "Some  here.\n\nEnd."
+        );
+        assert_eq!(extract("~~~\nfenced\n~~~\n``code with ` tick``"), "");
+    }
+
+    #[test]
+    fn removes_urls() {
+        assert_eq!(
+            extract("See [the docs](https://example.com/docs \"Title\") and ![alt](a.png)."),
+            "See the docs and ."
+        );
+        assert_eq!(
+            extract("Visit <https://example.com>, <me@example.com> or https://example.org now."),
+            "Visit ,  or  now."
+        );
+        assert_eq!(extract("At www.example.com (http://example.com) too."), "At   too.");
+        assert_eq!(extract("[link]: https://example.com\nText [link]."), "Text link.");
+    }
+
+    #[test]
+    fn separates_blocks() {
+        assert_eq!(
+            extract("# Title\nText without a full stop\n## Section\nMore"),
+            "Title\n\nText without a full stop\n\nSection\n\nMore"
+        );
+        assert_eq!(extract("Title\n=====\nText"), "Title\n\nText");
+        assert_eq!(extract("- One\n- Two\n\n> Quote"), "One\n\nTwo\n\nQuote");
+        assert_eq!(extract("| A | B |\n|---|---|\n| 1 | 2 |\n\nEnd"), "A\nB\n1\n2\n\nEnd");
+        assert_eq!(extract("One <b>bold</b><br>Two"), "One bold\nTwo");
+    }
+}
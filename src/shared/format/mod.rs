@@ -0,0 +1,106 @@
+mod code;
+mod gutenberg;
+mod html;
+mod markdown;
+mod subtitles;
+
+use std::path::Path;
+
+pub use code::CodeMode;
+pub(crate) use code::{Code, Language};
+pub(crate) use gutenberg::Gutenberg;
+pub(crate) use html::{Elements, Markup, HTML};
+use markdown::Markdown;
+use subtitles::Subtitles;
+
+/// Extracts the text to analyze from the decoded content of a source that is pushed in pieces.
+pub trait Extractor: Send {
+    /// Appends the text of the next piece of content to the output.
+    fn push(&mut self, content: &str, output: &mut String);
+
+    /// Appends the remaining text to the output after all content was pushed.
+    fn finish(&mut self, _output: &mut String) {}
+
+    /// Returns the duration in seconds covered by the timings of the content, 0 if it has none.
+    fn duration(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Format of a source that determines how its text is extracted.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InputFormat {
+    /// Plain text that is analyzed as is
+    #[default]
+    Text,
+    /// HTML or XML, analyzed without markup, comments, scripts and styles
+    Html,
+    /// Markdown, analyzed without code, URLs and front matter
+    Markdown,
+    /// EPUB e-book, analyzed as the text of its chapters in reading order
+    Epub,
+    /// Word document, analyzed as the text of its body
+    Docx,
+    /// OpenDocument text, analyzed as the text of its body
+    Odt,
+    /// SubRip, WebVTT or ASS/SSA subtitles, analyzed as the text of their cues
+    Subtitles,
+}
+impl InputFormat {
+    /// Names of the formats accepted by [`Self::from_name`].
+    pub const NAMES: &'static [&'static str] = &[
+        "text",
+        "html",
+        "markdown",
+        "epub",
+        "docx",
+        "odt",
+        "subtitles",
+    ];
+
+    /// Returns the format with a name in [`Self::NAMES`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "html" => Some(Self::Html),
+            "markdown" | "md" => Some(Self::Markdown),
+            "epub" => Some(Self::Epub),
+            "docx" => Some(Self::Docx),
+            "odt" => Some(Self::Odt),
+            "subtitles" | "srt" | "vtt" | "ass" | "ssa" => Some(Self::Subtitles),
+            _ => None,
+        }
+    }
+
+    /// Returns the format of a file by its extension, [`Self::Text`] for unknown extensions.
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        match extension.map(|extension| extension.to_ascii_lowercase()).as_deref() {
+            Some("html" | "htm" | "xhtml" | "xml") => Self::Html,
+            Some("md" | "markdown" | "mdown" | "mkd") => Self::Markdown,
+            Some("epub") => Self::Epub,
+            Some("docx") => Self::Docx,
+            Some("odt") => Self::Odt,
+            Some("srt" | "vtt" | "ass" | "ssa") => Self::Subtitles,
+            _ => Self::Text,
+        }
+    }
+
+    /// Returns whether sources of the format are zip packages of XML documents.
+    pub(crate) fn is_document(self) -> bool {
+        matches!(self, Self::Epub | Self::Docx | Self::Odt)
+    }
+
+    /// Returns the extractor of the format, `None` if the content is analyzed as is.
+    ///
+    /// The text of documents is extracted from their packages before it is pushed.
+    pub(crate) fn extractor(self) -> Option<Box<dyn Extractor>> {
+        match self {
+            Self::Text | Self::Epub | Self::Docx | Self::Odt => None,
+            Self::Html => Some(Box::<Markup>::default()),
+            Self::Markdown => Some(Box::<Markdown>::default()),
+            Self::Subtitles => Some(Box::<Subtitles>::default()),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use super::Extractor;
+
+/// Parses a SubRip (`00:01:02,500`), WebVTT (`01:02.500`) or ASS (`0:01:02.50`) timestamp into
+/// seconds.
+fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in timestamp.trim().split(':') {
+        let part = part.replace(',', ".");
+        if part.is_empty() || !part.bytes().all(|byte| byte.is_ascii_digit() || byte == b'.') {
+            return None;
+        }
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+/// Returns the text of a cue line without tags and with the entities of WebVTT decoded.
+///
+/// HTML-like tags (`<i>`, `<v Speaker>`, `<00:01.000>`) and ASS override blocks (`{\i1}`) are
+/// removed, and the ASS line breaks `\N`, `\n` and hard space `\h` are replaced with spaces.
+fn strip_markup(line: &str, output: &mut String) {
+    let line = line.replace("\\N", " ").replace("\\n", " ").replace("\\h", " ");
+    let mut text = String::with_capacity(line.len());
+    let mut close = None;
+    for c in line.chars() {
+        match (close, c) {
+            (None, '<') => close = Some('>'),
+            (None, '{') => close = Some('}'),
+            (None, c) => text.push(c),
+            (Some(end), c) if c == end => close = None,
+            (Some(_), _) => (),
+        }
+    }
+    if text.contains('&') {
+        for (entity, decoded) in [
+            ("&lt;", "<"),
+            ("&gt;", ">"),
+            ("&nbsp;", " "),
+            ("&amp;", "&"),
+        ] {
+            text = text.replace(entity, decoded);
+        }
+    }
+    output.push_str(&text);
+}
+
+/// Extractor of the dialogue of SubRip, WebVTT and ASS/SSA subtitles.
+///
+/// Cue numbers and identifiers, timings, notes, styles and markup are removed. Every cue is
+/// separated as a line, so it ends a sentence, and the time covered by the cues is accumulated for
+/// the words per minute.
+#[derive(Default)]
+pub struct Subtitles {
+    /// Incomplete line of the last pushed content
+    line:          String,
+    /// Text of the current cue
+    cue:           String,
+    /// Whether the lines after a SubRip or WebVTT timing line are cue text
+    in_cue:        bool,
+    /// Whether the content is ASS/SSA, detected from its section headers
+    ass:           bool,
+    /// Whether the current ASS/SSA section is the events section with the dialogue
+    events:        bool,
+    /// Indices of the start, end and text fields of ASS dialogue lines
+    ass_fields:    (usize, usize, usize),
+    /// Seconds covered by the cues so far, overlapping cues counted once
+    duration:      f64,
+    /// End of the latest cue in seconds
+    covered_until: f64,
+}
+impl Subtitles {
+    fn add_timing(&mut self, start: f64, end: f64) {
+        let start = start.max(self.covered_until);
+        if end > start {
+            self.duration += end - start;
+        }
+        self.covered_until = self.covered_until.max(end);
+    }
+
+    fn end_cue(&mut self, output: &mut String) {
+        let cue = self.cue.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !cue.is_empty() {
+            output.push_str(&cue);
+            output.push('\n');
+        }
+        self.cue.clear();
+        self.in_cue = false;
+    }
+
+    fn ass_line(&mut self, line: &str, output: &mut String) {
+        if !self.events {
+            return;
+        }
+        let (start, end, text) = self.ass_fields;
+        if let Some(format) = line.strip_prefix("Format:") {
+            let fields = format.split(',').map(str::trim).collect::<Vec<_>>();
+            let index = |name: &str, default| {
+                fields
+                    .iter()
+                    .position(|field| field.eq_ignore_ascii_case(name))
+                    .unwrap_or(default)
+            };
+            self.ass_fields = (index("Start", 1), index("End", 2), index("Text", fields.len() - 1));
+        } else if let Some(dialogue) = line.strip_prefix("Dialogue:") {
+            // The text is the last field and can contain commas
+            let fields = dialogue.splitn(text + 1, ',').collect::<Vec<_>>();
+            if let (Some(start), Some(end)) = (
+                fields.get(start).and_then(|start| parse_timestamp(start)),
+                fields.get(end).and_then(|end| parse_timestamp(end)),
+            ) {
+                self.add_timing(start, end);
+            }
+            if let Some(text) = fields.get(text) {
+                strip_markup(text, &mut self.cue);
+                self.end_cue(output);
+            }
+        }
+    }
+
+    fn text_line(&mut self, line: &str, output: &mut String) {
+        if line.trim().is_empty() {
+            self.end_cue(output);
+        } else if let Some((start, end)) = line.split_once("-->") {
+            self.end_cue(output);
+            let end = end.split_whitespace().next().unwrap_or_default();
+            if let (Some(start), Some(end)) = (parse_timestamp(start), parse_timestamp(end)) {
+                self.add_timing(start, end);
+                self.in_cue = true;
+            }
+        } else if self.in_cue {
+            strip_markup(line, &mut self.cue);
+            self.cue.push(' ');
+        }
+    }
+
+    fn process_line(&mut self, line: &str, output: &mut String) {
+        let line = line.trim_start_matches('\u{feff}').trim_end_matches(['\r', '\n']);
+        let section = line.trim();
+        if !self.in_cue && section.starts_with('[') && section.ends_with(']') {
+            let events = section.eq_ignore_ascii_case("[Events]");
+            if events || section.eq_ignore_ascii_case("[Script Info]") {
+                self.ass = true;
+            }
+            self.events = events;
+            self.ass_fields = (1, 2, 9);
+        }
+        match self.ass {
+            true => self.ass_line(line.trim_start(), output),
+            false => self.text_line(line, output),
+        }
+    }
+}
+impl Extractor for Subtitles {
+    fn push(&mut self, content: &str, output: &mut String) {
+        let mut content = content;
+        while let Some(index) = content.find('\n') {
+            let (line, rest) = content.split_at(index + 1);
+            content = rest;
+            match self.line.is_empty() {
+                true => self.process_line(line, output),
+                false => {
+                    let mut pending = std::mem::take(&mut self.line);
+                    pending.push_str(line);
+                    self.process_line(&pending, output);
+                }
+            }
+        }
+        self.line.push_str(content);
+    }
+
+    fn finish(&mut self, output: &mut String) {
+        let line = std::mem::take(&mut self.line);
+        self.process_line(&line, output);
+        self.end_cue(output);
+    }
+
+    fn duration(&self) -> f64 {
+        self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Extracts the text and duration of the content pushed at once and one character at a time.
+    fn extract(content: &str) -> (String, f64) {
+        let mut whole = String::new();
+        let mut subtitles = Subtitles::default();
+        subtitles.push(content, &mut whole);
+        subtitles.finish(&mut whole);
+        let duration = subtitles.duration();
+
+        let mut pieces = String::new();
+        let mut subtitles = Subtitles::default();
+        let mut buffer = [0; 4];
+        for c in content.chars() {
+            subtitles.push(c.encode_utf8(&mut buffer), &mut pieces);
+        }
+        subtitles.finish(&mut pieces);
+        assert_eq!(whole, pieces);
+        assert_eq!(duration, subtitles.duration());
+        (whole, duration)
+    }
+
+    #[test]
+    fn parses_timestamps() {
+        assert_eq!(parse_timestamp("00:01:02,500"), Some(62.5));
+        assert_eq!(parse_timestamp(" 01:02.500 "), Some(62.5));
+        assert_eq!(parse_timestamp("1:01:02.50"), Some(3662.5));
+        assert_eq!(parse_timestamp("00:01:-2,500"), None);
+        assert_eq!(parse_timestamp("00::02"), None);
+    }
+
+    #[test]
+    fn extracts_subrip_cues() {
+        let (text, duration) = extract(
+            "\u{feff}1\r\n00:00:01,000 --> 00:00:04,000\r\n<i>Hello</i> there,\r\nfriend.\r\n\r\n\
+             2\r\n00:00:05,000 --> 00:00:06,500\r\n{\\an8}Bye &amp; bye.\r\n",
+        );
+        assert_eq!(text, "Hello there, friend.\nBye & bye.\n");
+        assert_eq!(duration, 4.5);
+    }
+
+    #[test]
+    fn counts_overlapping_cues_once() {
+        let (text, duration) = extract(
+            "00:00:00,000 --> 00:00:04,000\nOne\n\n00:00:02,000 --> 00:00:06,000\nTwo\n\n\
+             00:00:03,000 --> 00:00:05,000\nThree\n\n00:00:10,000 --> 00:00:11,000\nFour",
+        );
+        assert_eq!(text, "One\nTwo\nThree\nFour\n");
+        assert_eq!(duration, 7.0);
+    }
+
+    #[test]
+    fn skips_webvtt_headers_and_blocks() {
+        let (text, duration) = extract(
+            "WEBVTT - Title\nKind: captions\n\nNOTE A note\nover two lines\n\nSTYLE\n::cue { color: \
+             red }\n\nintro\n00:01.000 --> 00:03.000 align:start position:10%\n<v Roger>Hi \
+             <00:02.000>there&nbsp;&lt;3\n\nNOTE Another note\n",
+        );
+        assert_eq!(text, "Hi there <3\n");
+        assert_eq!(duration, 2.0);
+    }
+
+    #[test]
+    fn extracts_ass_dialogue() {
+        let (text, duration) = extract(
+            "[Script Info]\nTitle: Dialogue: not text\n\n[V4+ Styles]\nFormat: Name, Fontname\n\
+             Style: Default,Arial\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, \
+             MarginR, MarginV, Effect, Text\nDialogue: 0,0:00:01.00,0:00:03.50,Default,,0,0,0,,\
+             {\\i1}Well,{\\i0} yes,\\Nno.\nComment: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,Hidden\n",
+        );
+        assert_eq!(text, "Well, yes, no.\n");
+        assert_eq!(duration, 2.5);
+
+        let (text, duration) = extract(
+            "[Events]\nFormat: Start, End, Text\nDialogue: 0:00:02.00,0:00:03.00,Moved, fields\n",
+        );
+        assert_eq!(text, "Moved, fields\n");
+        assert_eq!(duration, 1.0);
+    }
+}
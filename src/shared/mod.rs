@@ -1,199 +1,416 @@
+mod archive;
+mod compress;
+mod counter;
+mod decode;
+mod document;
+mod export;
+mod format;
+mod normalize;
+mod readability;
+mod stemmer;
+mod stopwords;
+mod tokenizer;
 mod uhash;
 mod ustring;
+mod walk;
 
 use std::{
     collections::HashMap,
+    fs::File,
     hash::BuildHasherDefault,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
 use dashmap::DashMap;
+
 use entangled::{ThreadPool, ThreadPoolDescriptor};
 use futures::{executor::block_on, future::join_all};
 use pathdiff::diff_paths;
-use rayon::iter::{
-    IndexedParallelIterator, IntoParallelRefIterator, ParallelBridge, ParallelIterator,
+use rayon::iter::{IntoParallelRefIterator, ParallelBridge, ParallelIterator};
+
+use archive::{is_archive, read_archive};
+use compress::uncompressed_path;
+use counter::Counter;
+use decode::{is_binary_error, read_file, Decoded};
+use document::read_document;
+use format::{Code, Extractor, Gutenberg, Language};
+use readability::update_readability;
+use walk::walk;
+
+pub use encoding_rs::Encoding;
+pub use export::{write_csv, write_json, write_tsv};
+pub use format::{CodeMode, InputFormat};
+pub use normalize::Normalization;
+pub use readability::syllables;
+pub use stemmer::Stemmer;
+pub use stopwords::Stopwords;
+pub use tokenizer::{
+    builtin_tokenizer, IdentifierTokenizer, RegexTokenizer, Tokenizer, UnicodeTokenizer,
+    WhitespaceTokenizer, TOKENIZERS,
 };
-use unicode_segmentation::UnicodeSegmentation;
-use walkdir::WalkDir;
+pub use uhash::{IdentityHashSet, IdentityHasher};
+pub use ustring::{Interner, UniqueString};
 
-use uhash::IdentityHasher;
-use ustring::UniqueString;
+/// Map of interned words to the number of their occurrences.
+pub type WordFreqMap = DashMap<UniqueString, usize, BuildHasherDefault<IdentityHasher>>;
+/// Map of interned stems to the interned surface forms that were counted as them.
+pub type WordFormsMap =
+    DashMap<UniqueString, IdentityHashSet<UniqueString>, BuildHasherDefault<IdentityHasher>>;
 
+/// Statistics of a single analyzed source, or the combined statistics of multiple sources.
+///
+/// Instances are only created by [`analyze`], new fields may be added in minor releases.
 #[derive(Default, Debug, Clone)]
+#[non_exhaustive]
 pub struct Analysis {
-    pub file:             Option<PathBuf>,
-    pub word_count:       usize,
-    pub char_count:       usize,
-    pub sent_count:       usize,
-    pub para_count:       usize,
-    pub word_uniqs:       usize,
-    pub word_freq:        Vec<(usize, UniqueString)>,
-    pub word_freq_map:    DashMap<UniqueString, usize, BuildHasherDefault<IdentityHasher>>,
-    pub word_dist_mean:   f64,
-    pub word_dist_stddev: f64,
-    pub word_dist_median: f64,
-    pub word_dist_mode:   f64,
+    /// Path of the analyzed file, `None` for in-memory content and combined analyses
+    pub file:                  Option<PathBuf>,
+    /// Number of words, excluding stopwords
+    pub word_count:            usize,
+    /// Number of grapheme clusters
+    pub char_count:            usize,
+    /// Number of sentences
+    pub sent_count:            usize,
+    /// Number of paragraphs separated by blank lines
+    pub para_count:            usize,
+    /// Number of distinct words, excluding stopwords
+    pub word_uniqs:            usize,
+    /// Words with their number of occurrences, sorted by descending frequency
+    pub word_freq:             Vec<(usize, UniqueString)>,
+    /// Words with their number of occurrences
+    pub word_freq_map:         WordFreqMap,
+    /// Surface forms of the words counted as each stem or lemma, empty if stemming is disabled
+    pub word_forms_map:        WordFormsMap,
+    /// Mean of the word frequencies
+    pub word_dist_mean:        f64,
+    /// Standard deviation of the word frequencies
+    pub word_dist_stddev:      f64,
+    /// Median of the word frequencies
+    pub word_dist_median:      f64,
+    /// Mode of the word frequencies, averaged if there are multiple
+    pub word_dist_mode:        f64,
+    /// Number of stopwords excluded from the word frequencies
+    pub stopword_count:        usize,
+    /// Number of distinct stopwords excluded from the word frequencies, regardless of their case
+    pub stopword_uniqs:        usize,
+    /// Stopwords in lowercase with their number of occurrences in any case
+    pub stopword_freq_map:     WordFreqMap,
+    /// Number of words including stopwords
+    pub all_word_count:        usize,
+    /// Number of distinct words including stopwords
+    pub all_word_uniqs:        usize,
+    /// Mean of the word frequencies including stopwords
+    pub all_word_dist_mean:    f64,
+    /// Standard deviation of the word frequencies including stopwords
+    pub all_word_dist_stddev:  f64,
+    /// Median of the word frequencies including stopwords
+    pub all_word_dist_median:  f64,
+    /// Mode of the word frequencies including stopwords, averaged if there are multiple
+    pub all_word_dist_mode:    f64,
+    /// Number of syllables of all words including stopwords
+    pub syllable_count:        usize,
+    /// Number of words with three or more syllables including stopwords
+    pub polysyllable_count:    usize,
+    /// Number of letters and digits of all words including stopwords
+    pub letter_count:          usize,
+    /// Flesch reading ease score
+    pub flesch_reading_ease:   f64,
+    /// Flesch-Kincaid grade level
+    pub flesch_kincaid_grade:  f64,
+    /// Gunning fog index
+    pub gunning_fog:           f64,
+    /// SMOG grade
+    pub smog:                  f64,
+    /// Coleman-Liau index
+    pub coleman_liau:          f64,
+    /// Automated readability index
+    pub automated_readability: f64,
+    /// Number of word n-grams, 0 if n-grams are not counted
+    pub ngram_count:           usize,
+    /// Word n-grams with their number of occurrences, sorted by descending frequency
+    pub ngram_freq:            Vec<(usize, UniqueString)>,
+    /// Word n-grams with their number of occurrences
+    pub ngram_freq_map:        WordFreqMap,
+    /// Seconds covered by the cues of subtitles, overlapping cues counted once
+    pub cue_duration:          f64,
+    /// Number of words including stopwords in subtitles with cues, the words that are timed
+    pub cue_word_count:        usize,
+    /// Words including stopwords per minute of subtitle cues, 0 if not enabled
+    pub words_per_minute:      f64,
+    /// Encoding the file was decoded with, `None` for in-memory content and combined analyses
+    pub encoding:              Option<&'static Encoding>,
+    /// Number of malformed bytes that were replaced when decoding in lossy mode
+    pub replaced_bytes:        usize,
+    /// Number of files that were skipped because their content is binary, only in combined analyses
+    pub skipped_binary:        usize,
 }
 
+/// Options for [`analyze`] and the reports built from its results.
+///
+/// Construct with `..Default::default()` to stay compatible with fields added in minor releases.
 #[derive(Default, Debug, Clone)]
 pub struct Args {
+    /// Normalize casing by lowercasing each word
     pub lowercase:       bool,
+    /// Number of top words to show per source (0 = all)
     pub top_words:       usize,
+    /// Number of least occuring words to show per source
     pub bottom_words:    usize,
+    /// Iterate through subdirectories
     pub recursive:       bool,
+    /// Follow symlinks
     pub follow_symlinks: bool,
+    /// Glob patterns of files to analyze in directories and archives, all files if empty
+    pub include:         Vec<String>,
+    /// Glob patterns of files and directories to skip in directories and archives
+    pub exclude:         Vec<String>,
+    /// Extensions of files to analyze in directories and archives, all files if empty
+    pub extensions:      Vec<String>,
+    /// Don't skip files listed in `.gitignore` and `.ignore` files
+    pub no_ignore:       bool,
+    /// Don't skip hidden files and directories
+    pub hidden:          bool,
+    /// Path of a file that the results will be written to
     pub outfile:         Option<String>,
+    /// Hide sources without any words in reports
     pub hide_empty:      bool,
+    /// Show matching emojis for words in reports
     pub emojis:          bool,
+    /// Show a combined analysis with all words in reports
     pub show_all_words:  bool,
+    /// Number of words in the n-grams counted within sentences (< 2 = disabled)
+    pub ngrams:          usize,
+    /// Words to exclude from the word frequencies and n-grams consisting only of stopwords
+    pub stopwords:       Option<Arc<Stopwords>>,
+    /// Encoding to decode files with instead of detecting it from their first block
+    pub encoding:        Option<&'static Encoding>,
+    /// Replace malformed byte sequences instead of failing to analyze the file
+    pub lossy:           bool,
+    /// Analyze files with binary content instead of skipping them
+    pub binary:          bool,
+    /// Format of all sources instead of detecting it from the file extension
+    pub input_format:    Option<InputFormat>,
+    /// Calculate the words per minute of subtitles from their cue timings
+    pub wpm:             bool,
+    /// Part of source files to analyze instead of their whole content, other files are unaffected
+    pub code_mode:       Option<CodeMode>,
+    /// Only analyze the text between the start and end markers of Project Gutenberg e-books
+    pub strip_gutenberg: bool,
+    /// Tokenizer of words and sentences, the [`UnicodeTokenizer`] if not set
+    pub tokenizer:       Option<Arc<dyn Tokenizer>>,
+    /// Stemmer or lemmatizer whose stems are counted instead of the words
+    pub stemmer:         Option<Arc<Stemmer>>,
+    /// Unicode normalization form that words are converted to before they are counted
+    pub normalize:       Option<Normalization>,
+    /// Normalize casing with full Unicode case folding instead of lowercasing
+    pub casefold:        bool,
+    /// Strip diacritics such as accents from words
+    pub strip_accents:   bool,
+}
+impl Analysis {
+    /// Returns the surface forms that were counted as a stem or lemma, sorted alphabetically.
+    pub fn word_forms(&self, word: &UniqueString) -> Vec<UniqueString> {
+        let mut forms = self
+            .word_forms_map
+            .get(word)
+            .map(|forms| forms.iter().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        forms.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        forms
+    }
 }
 
-fn update_dists(analysis: &mut Analysis) {
-    if analysis.word_freq.is_empty() {
-        return;
+fn sorted_freq(map: &WordFreqMap) -> Vec<(usize, UniqueString)> {
+    let mut freq = map.iter().map(|item| (*item.value(), item.key().clone())).collect::<Vec<_>>();
+    freq.sort_by(|(a, _), (b, _)| b.cmp(a));
+    freq
+}
+
+/// Returns the mean, standard deviation, median and mode of frequencies sorted in descending order.
+fn distribution(freq: &[usize]) -> (f64, f64, f64, f64) {
+    if freq.is_empty() {
+        return (0.0, 0.0, 0.0, 0.0);
     }
-    analysis.word_dist_mean =
-        analysis.word_freq.iter().map(|a| a.0).reduce(|a, b| a + b).unwrap_or_default() as f64
-            / analysis.word_uniqs as f64;
-    analysis.word_dist_stddev = (analysis
-        .word_freq
-        .iter()
-        .map(|a| (a.0 as f64 - analysis.word_dist_mean).powi(2))
-        .reduce(|a, b| a + b)
-        .unwrap_or_default()
-        / analysis.word_uniqs as f64)
-        .sqrt();
-    analysis.word_dist_median = match analysis.word_freq.len() % 2 == 0 {
-        true => {
-            (analysis.word_freq[(analysis.word_freq.len()) / 2 - 1].0
-                + analysis.word_freq[(analysis.word_freq.len()) / 2].0) as f64
-                / 2.0
-        }
-        false => analysis.word_freq[analysis.word_freq.len() / 2].0 as f64,
+    let mean = freq.iter().sum::<usize>() as f64 / freq.len() as f64;
+    let stddev =
+        (freq.iter().map(|&a| (a as f64 - mean).powi(2)).sum::<f64>() / freq.len() as f64).sqrt();
+    let median = match freq.len() % 2 == 0 {
+        true => (freq[freq.len() / 2 - 1] + freq[freq.len() / 2]) as f64 / 2.0,
+        false => freq[freq.len() / 2] as f64,
     };
 
     let mut mode_counts = HashMap::new();
-    analysis
-        .word_freq
-        .iter()
-        .for_each(|(freq, _)| *mode_counts.entry(*freq).or_insert(0) += 1);
-    let max_count = mode_counts.iter().max_by_key(|(_, &v)| v).map(|(_, &v)| v).unwrap_or(0);
-    if max_count > 0 {
-        let mut counts = 0;
-        analysis.word_dist_mode =
-            mode_counts.iter().filter(|(_, &v)| v == max_count).fold(0, |acc, (&k, _)| {
-                counts += 1;
-                acc + k
-            }) as f64
-                / counts as f64;
+    freq.iter().for_each(|freq| *mode_counts.entry(*freq).or_insert(0) += 1);
+    let max_count = mode_counts.values().max().copied().unwrap_or(0);
+    let mut counts = 0;
+    let mode = mode_counts.iter().filter(|(_, &v)| v == max_count).fold(0, |acc, (&k, _)| {
+        counts += 1;
+        acc + k
+    }) as f64
+        / counts as f64;
+
+    (mean, stddev, median, mode)
+}
+
+fn update_words_per_minute(analysis: &mut Analysis) {
+    if analysis.cue_duration > 0.0 {
+        analysis.words_per_minute = analysis.cue_word_count as f64 * 60.0 / analysis.cue_duration;
     }
 }
 
-async fn process(
-    source: AnalyzeSource, lowercase: bool,
-) -> Result<Analysis, (PathBuf, std::io::Error)> {
-    let (content, file) = match source {
-        AnalyzeSource::Content(content) => (content, None),
-        AnalyzeSource::Path(path) => (
-            std::fs::read_to_string(&path).map_err(|error| (path.clone(), error))?,
-            Some(path),
-        ),
-    };
+fn update_dists(analysis: &mut Analysis) {
+    analysis.word_uniqs = analysis.word_freq.len();
+    analysis.stopword_uniqs = analysis.stopword_freq_map.len();
+    analysis.all_word_count = analysis.word_count + analysis.stopword_count;
+    analysis.all_word_uniqs = analysis.word_uniqs + analysis.stopword_uniqs;
 
-    let mut analysis = Analysis {
-        file,
-        sent_count: content.unicode_sentences().count(),
-        para_count: content.replace("\r\n", "\n").split("\n\n").count(),
-        char_count: content.graphemes(true).count(),
-        ..Default::default()
+    let freq = analysis.word_freq.iter().map(|(freq, _)| *freq).collect::<Vec<_>>();
+    let dist = distribution(&freq);
+    (
+        analysis.word_dist_mean,
+        analysis.word_dist_stddev,
+        analysis.word_dist_median,
+        analysis.word_dist_mode,
+    ) = dist;
+
+    let dist = if analysis.stopword_freq_map.is_empty() {
+        dist
+    } else {
+        let mut freq = freq;
+        freq.extend(analysis.stopword_freq_map.iter().map(|item| *item.value()));
+        freq.sort_by(|a, b| b.cmp(a));
+        distribution(&freq)
     };
+    (
+        analysis.all_word_dist_mean,
+        analysis.all_word_dist_stddev,
+        analysis.all_word_dist_median,
+        analysis.all_word_dist_mode,
+    ) = dist;
+}
 
-    let map = DashMap::default();
-    let words = content.unicode_words().collect::<Vec<_>>();
-    analysis.word_count = words
-        .par_iter()
-        .chunks(12500)
-        .map(|words| {
-            let len = words.len();
-            for &word in words {
-                map.entry(if lowercase {
-                    UniqueString::from(word.to_lowercase())
-                } else {
-                    UniqueString::from(word)
-                })
-                .and_modify(|num| *num += 1)
-                .or_insert(1);
-            }
-            len
-        })
-        .sum();
-    map.iter().for_each(|item| {
-        let (word, count) = (item.key(), item.value());
-        analysis.word_freq.push((*count, *word));
-    });
-    analysis.word_freq.sort_by(|(a, _), (b, _)| b.cmp(a));
-    analysis.word_freq_map = map;
-    analysis.word_uniqs = analysis.word_freq.len();
-    update_dists(&mut analysis);
+/// Returns the extractor of a source in the format, which lexes source files in code mode and
+/// strips the boilerplate of Project Gutenberg e-books if enabled.
+fn extractor(format: InputFormat, file: Option<&Path>, args: &Args) -> Option<Box<dyn Extractor>> {
+    let language = file.and_then(|file| Language::from_path(&uncompressed_path(file)));
+    let extractor = match args.code_mode.zip(language) {
+        Some((mode, language)) => Some(Box::new(Code::new(language, mode)) as Box<dyn Extractor>),
+        None => format.extractor(),
+    };
+    match args.strip_gutenberg {
+        true => Some(Box::new(Gutenberg::new(extractor))),
+        false => extractor,
+    }
+}
 
+/// Analyzes a file whose content is pushed to the counter by `read`, with the format of the file
+/// detected from its path if it is not specified.
+fn process_file(
+    file: PathBuf, args: Arc<Args>, interner: Interner,
+    read: impl FnOnce(InputFormat, &Args, &mut Counter) -> std::io::Result<Decoded>,
+) -> Result<Analysis, (PathBuf, std::io::Error)> {
+    let format = args
+        .input_format
+        .unwrap_or_else(|| InputFormat::from_path(&uncompressed_path(&file)));
+    let extractor = extractor(format, Some(&file), &args);
+    let mut counter = Counter::new(Some(file.clone()), args.clone(), interner, extractor);
+    let decoded = read(format, &args, &mut counter).map_err(|error| (file, error))?;
+    let mut analysis = counter.finish();
+    analysis.encoding = Some(decoded.encoding);
+    analysis.replaced_bytes = decoded.replaced_bytes;
     Ok(analysis)
 }
 
-#[allow(dead_code)]
+async fn process(
+    source: AnalyzeSource, args: Arc<Args>, interner: Interner,
+) -> Vec<Result<Analysis, (PathBuf, std::io::Error)>> {
+    match source {
+        AnalyzeSource::Content(content) => {
+            let format = args.input_format.unwrap_or_default();
+            let extractor = extractor(format, None, &args);
+            let mut counter = Counter::new(None, args, interner, extractor);
+            counter.push(&content);
+            vec![Ok(counter.finish())]
+        }
+        AnalyzeSource::Path(path) if is_archive(&path) => read_archive(&path, args, interner),
+        AnalyzeSource::Path(path) => {
+            vec![process_file(
+                path.clone(),
+                args,
+                interner,
+                |format, args, counter| match format.is_document() {
+                    true => read_document(File::open(&path)?, format, args, counter),
+                    false => read_file(&path, args, counter),
+                },
+            )]
+        }
+    }
+}
+
+/// Input of [`analyze`].
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum AnalyzeSource {
+    /// Text content to analyze directly
     Content(String),
+    /// Path to a file, a directory of files or a zip or tar archive of files to analyze
     Path(PathBuf),
 }
 
+/// Analyzes the given sources in parallel.
+///
+/// Returns the analysis of every source file that could be read, and the combined analysis of
+/// all of them if there was at least one. Errors for individual files are reported through
+/// `on_error` and don't abort the analysis, `on_message`, `on_progress` and `on_increment` are
+/// called with status messages, the currently processed file and per finished source. If the
+/// thread pool can't be created, the error is reported through `on_error` and nothing is returned.
+///
+/// The words of each run are interned in a new [`Interner`], its memory is released once all
+/// returned analyses and the words taken from them are dropped.
 pub fn analyze<
     E: Fn(String) + Sync + Send,
     P: Fn(String) + Sync + Send,
     M: Fn(String) + Sync + Send,
     I: Fn(u64) + Sync + Send,
 >(
-    sources: &Vec<AnalyzeSource>, args: &Args, pwd: &Path, on_error: E, on_message: M,
-    on_progress: P, on_increment: I,
+    sources: &[AnalyzeSource], args: &Args, pwd: &Path, on_error: E, on_message: M, on_progress: P,
+    on_increment: I,
 ) -> (Vec<Analysis>, Option<Analysis>) {
-    let pool = ThreadPool::new(ThreadPoolDescriptor {
+    let pool = match ThreadPool::new(ThreadPoolDescriptor {
         num_threads: num_cpus::get(),
         ..Default::default()
-    })
-    .unwrap_or_else(|error| {
-        on_error(format!("{}", error));
-        std::process::exit(1)
-    });
+    }) {
+        Ok(pool) => pool,
+        Err(error) => {
+            on_error(format!("Could not create thread pool: {}", error));
+            return (Vec::new(), None);
+        }
+    };
     let tasks = Arc::new(Mutex::new(Vec::new()));
+    let args = Arc::new(args.clone());
+    let interner = Interner::new();
 
     sources.par_iter().for_each(|source| {
         match source {
             AnalyzeSource::Content(content) => {
                 on_progress("Analyzing...".to_string());
-                tasks.lock().unwrap().push(
-                    pool.spawn(process(AnalyzeSource::Content(content.to_owned()), args.lowercase)),
-                );
+                tasks.lock().unwrap().push(pool.spawn(process(
+                    AnalyzeSource::Content(content.to_owned()),
+                    args.clone(),
+                    interner.clone(),
+                )));
             }
             AnalyzeSource::Path(path) => {
-                let walk = WalkDir::new(path)
-                    .follow_links(args.follow_symlinks)
-                    .max_depth(if args.recursive { std::usize::MAX } else { 1 })
-                    .sort_by_file_name();
-                walk.into_iter()
-                    .filter_map(|path| {
-                        path.map_err(|error| {
-                            on_message(format!("{}", error));
-                            error
-                        })
-                        .map_or(None, |path| {
-                            if path.file_type().is_file() {
-                                Some(path)
-                            } else {
-                                None
-                            }
-                        })
-                    })
+                let walk = match walk(path, &args) {
+                    Ok(walk) => walk,
+                    Err(error) => {
+                        on_error(format!("{}", error));
+                        on_increment(1);
+                        return;
+                    }
+                };
+                walk.filter_map(|file| file.map_err(|error| on_message(format!("{}", error))).ok())
                     .par_bridge()
                     .for_each(|file| {
                         on_progress(format!(
@@ -204,7 +421,8 @@ pub fn analyze<
                         ));
                         tasks.lock().unwrap().push(pool.spawn(process(
                             AnalyzeSource::Path(file.path().to_owned()),
-                            args.lowercase,
+                            args.clone(),
+                            interner.clone(),
                         )));
                     });
             }
@@ -213,11 +431,16 @@ pub fn analyze<
     });
 
     let tasks = Arc::try_unwrap(tasks).unwrap().into_inner().unwrap();
-    let analyses = block_on(join_all(tasks));
+    let analyses = block_on(join_all(tasks)).into_iter().flatten().collect::<Vec<_>>();
 
     let mut total: Option<Analysis> = None;
+    let mut skipped_binary = 0;
     for analysis in analyses.iter() {
         if let Err((path, error)) = analysis {
+            if is_binary_error(error) {
+                skipped_binary += 1;
+                continue;
+            }
             on_error(format!(
                 "Failed to analyze {}: {}",
                 diff_paths(path, pwd).unwrap_or_else(|| path.to_owned()).display(),
@@ -236,26 +459,102 @@ pub fn analyze<
                 let (word, count) = (item.key(), item.value());
                 total
                     .word_freq_map
-                    .entry(*word)
+                    .entry(word.clone())
+                    .and_modify(|num| *num += *count)
+                    .or_insert(*count);
+            }
+            for item in analysis.word_forms_map.iter() {
+                let (word, forms) = (item.key(), item.value());
+                total
+                    .word_forms_map
+                    .entry(word.clone())
+                    .or_default()
+                    .extend(forms.iter().cloned());
+            }
+            total.stopword_count += analysis.stopword_count;
+            total.syllable_count += analysis.syllable_count;
+            total.polysyllable_count += analysis.polysyllable_count;
+            total.letter_count += analysis.letter_count;
+            for item in analysis.stopword_freq_map.iter() {
+                let (word, count) = (item.key(), item.value());
+                total
+                    .stopword_freq_map
+                    .entry(word.clone())
+                    .and_modify(|num| *num += *count)
+                    .or_insert(*count);
+            }
+            total.replaced_bytes += analysis.replaced_bytes;
+            total.cue_duration += analysis.cue_duration;
+            total.cue_word_count += analysis.cue_word_count;
+            total.ngram_count += analysis.ngram_count;
+            for item in analysis.ngram_freq_map.iter() {
+                let (ngram, count) = (item.key(), item.value());
+                total
+                    .ngram_freq_map
+                    .entry(ngram.clone())
                     .and_modify(|num| *num += *count)
                     .or_insert(*count);
             }
         } else {
             let mut analysis = analysis.clone();
             analysis.file = None;
+            analysis.encoding = None;
             analysis.word_freq.clear();
+            analysis.ngram_freq.clear();
             total = Some(analysis);
         }
     }
+    if skipped_binary > 0 {
+        total.get_or_insert_with(Analysis::default).skipped_binary = skipped_binary;
+    }
     let analyses = analyses.into_iter().filter_map(|analysis| analysis.ok()).collect();
     if let Some(analysis) = &mut total {
-        analysis.word_freq_map.iter().for_each(|item| {
-            let (word, count) = (item.key(), item.value());
-            analysis.word_freq.push((*count, *word))
-        });
-        analysis.word_freq.sort_by(|(a, _), (b, _)| b.cmp(a));
+        analysis.word_freq = sorted_freq(&analysis.word_freq_map);
+        analysis.ngram_freq = sorted_freq(&analysis.ngram_freq_map);
         update_dists(analysis);
+        update_readability(analysis);
+        if args.wpm {
+            update_words_per_minute(analysis);
+        }
     }
 
     (analyses, total)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_words_per_minute_of_timed_words() {
+        let directory = std::env::temp_dir().join(format!("wordstat-wpm-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let subtitles = directory.join("cues.srt");
+        let text = directory.join("book.txt");
+        std::fs::write(&subtitles, "1\n00:00:00,000 --> 00:00:30,000\nOne two three four five.\n")
+            .unwrap();
+        std::fs::write(&text, "Words without any timing. ".repeat(100)).unwrap();
+
+        let args = Args {
+            wpm: true,
+            ..Default::default()
+        };
+        let sources = [AnalyzeSource::Path(subtitles), AnalyzeSource::Path(text)];
+        let (analyses, total) =
+            analyze(&sources, &args, &directory, |error| panic!("{}", error), drop, drop, drop);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        let words_per_minute = |name: &str| {
+            let analysis = analyses
+                .iter()
+                .find(|analysis| analysis.file.as_ref().is_some_and(|file| file.ends_with(name)));
+            analysis.unwrap().words_per_minute
+        };
+        assert_eq!(words_per_minute("cues.srt"), 10.0);
+        assert_eq!(words_per_minute("book.txt"), 0.0);
+        let total = total.unwrap();
+        assert_eq!(total.all_word_count, 405);
+        assert_eq!(total.cue_word_count, 5);
+        assert_eq!(total.words_per_minute, 10.0);
+    }
+}
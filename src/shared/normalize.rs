@@ -0,0 +1,104 @@
+use std::borrow::Cow;
+
+use unicode_normalization::{is_nfc_quick, is_nfkc_quick, IsNormalized, UnicodeNormalization};
+
+/// Unicode normalization form that words are converted to before they are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Canonical composition, which merges precomposed and decomposed characters
+    Nfc,
+    /// Compatibility composition, which additionally merges compatibility characters such as
+    /// ligatures and full-width forms with the characters they stand for
+    Nfkc,
+}
+impl Normalization {
+    /// Names of the forms accepted by [`Self::from_name`].
+    pub const NAMES: &'static [&'static str] = &["nfc", "nfkc"];
+
+    /// Returns the form with a name in [`Self::NAMES`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "nfc" => Some(Self::Nfc),
+            "nfkc" => Some(Self::Nfkc),
+            _ => None,
+        }
+    }
+
+    /// Returns the word in the normalization form.
+    pub fn normalize(self, word: &str) -> Cow<'_, str> {
+        match self {
+            Self::Nfc if is_nfc_quick(word.chars()) == IsNormalized::Yes => Cow::Borrowed(word),
+            Self::Nfkc if is_nfkc_quick(word.chars()) == IsNormalized::Yes => Cow::Borrowed(word),
+            Self::Nfc => Cow::Owned(word.nfc().collect()),
+            Self::Nfkc => Cow::Owned(word.nfkc().collect()),
+        }
+    }
+}
+
+/// Returns whether a character is a combining diacritical mark such as an accent.
+///
+/// Other combining marks like the vowel signs of Indic scripts are part of the letters.
+fn is_diacritic(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'     // Combining diacritical marks
+        | '\u{1AB0}'..='\u{1AFF}'   // Combining diacritical marks extended
+        | '\u{1DC0}'..='\u{1DFF}'   // Combining diacritical marks supplement
+        | '\u{20D0}'..='\u{20FF}'   // Combining diacritical marks for symbols
+        | '\u{FE20}'..='\u{FE2F}'   // Combining half marks
+    )
+}
+
+/// Returns the word with full Unicode case folding applied, so that words like "Straße" and
+/// "STRASSE" are the same.
+pub(crate) fn casefold(word: &str) -> Cow<'_, str> {
+    match word.is_ascii() {
+        true if !word.bytes().any(|byte| byte.is_ascii_uppercase()) => Cow::Borrowed(word),
+        true => Cow::Owned(word.to_ascii_lowercase()),
+        false => Cow::Owned(caseless::default_case_fold_str(word)),
+    }
+}
+
+/// Returns the word without the diacritics of its canonical decomposition, composed in NFC.
+pub(crate) fn strip_diacritics(word: &str) -> Cow<'_, str> {
+    match word.is_ascii() {
+        true => Cow::Borrowed(word),
+        false => Cow::Owned(word.nfd().filter(|&c| !is_diacritic(c)).nfc().collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_case() {
+        assert_eq!(casefold("Straße"), casefold("STRASSE"));
+        assert_eq!(casefold("Straße"), "strasse");
+        assert_eq!(casefold("ΣΊΣΥΦΟΣ"), "σίσυφοσ");
+        assert!(matches!(casefold("lowercase"), Cow::Borrowed("lowercase")));
+        assert_eq!(casefold("ASCII"), "ascii");
+    }
+
+    #[test]
+    fn strips_diacritics() {
+        assert_eq!(strip_diacritics("café"), "cafe");
+        assert_eq!(strip_diacritics("cafe\u{301}"), "cafe");
+        assert_eq!(strip_diacritics("Ångström naïve"), "Angstrom naive");
+        assert!(matches!(strip_diacritics("cafe"), Cow::Borrowed("cafe")));
+        // Vowel signs and viramas are part of the letters of Indic scripts
+        assert_eq!(strip_diacritics("हिन्दी"), "हिन्दी");
+        assert_eq!(strip_diacritics("किताबें"), "किताबें");
+    }
+
+    #[test]
+    fn normalizes_words() {
+        let decomposed = "cafe\u{301}";
+        assert_eq!(Normalization::Nfc.normalize(decomposed), "café");
+        assert!(matches!(Normalization::Nfc.normalize("café"), Cow::Borrowed("café")));
+        assert_eq!(Normalization::Nfc.normalize("ﬁne"), "ﬁne");
+        assert_eq!(Normalization::Nfkc.normalize("ﬁne"), "fine");
+        assert_eq!(Normalization::Nfkc.normalize("ＡＢＣ"), "ABC");
+        assert_eq!(Normalization::from_name("NFKC"), Some(Normalization::Nfkc));
+        assert_eq!(Normalization::from_name("nfd"), None);
+    }
+}
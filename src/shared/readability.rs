@@ -0,0 +1,153 @@
+use super::Analysis;
+
+fn is_vowel(c: char) -> bool {
+    matches!(
+        c,
+        'a' | 'e'
+            | 'i'
+            | 'o'
+            | 'u'
+            | 'y'
+            | 'à'..='æ'
+            | 'è'..='ë'
+            | 'ì'..='ï'
+            | 'ò'..='ö'
+            | 'ø'..='ü'
+            | 'ÿ'
+            | 'œ'
+    )
+}
+
+/// Estimates the number of syllables of an English word.
+///
+/// Counts groups of vowels and discounts silent endings, words without letters count as one
+/// syllable.
+pub fn syllables(word: &str) -> usize {
+    let mut count = 0;
+    let mut last_vowel = false;
+    // Last three letters for the silent endings, padded with spaces in shorter words
+    let mut last = [' '; 3];
+    for c in word.chars().filter(|c| c.is_alphabetic()).flat_map(char::to_lowercase) {
+        let vowel = is_vowel(c);
+        if vowel && !last_vowel {
+            count += 1;
+        }
+        last_vowel = vowel;
+        last = [last[1], last[2], c];
+    }
+    if count > 1 {
+        match last {
+            [c, 'l', 'e'] if !is_vowel(c) => (),
+            [c, 'e', 'd'] if !is_vowel(c) && !matches!(c, 't' | 'd') => count -= 1,
+            [c, 'e', 's'] if !is_vowel(c) && !matches!(c, 's' | 'x' | 'z' | 'c' | 'g' | 'h') => {
+                count -= 1
+            }
+            [_, c, 'e'] if !is_vowel(c) => count -= 1,
+            _ => (),
+        }
+    }
+    count.max(1)
+}
+
+/// Updates the readability indices of an analysis from its word, sentence and syllable counts.
+pub fn update_readability(analysis: &mut Analysis) {
+    let words = analysis.all_word_count as f64;
+    let sentences = analysis.sent_count as f64;
+    if analysis.all_word_count == 0 || analysis.sent_count == 0 {
+        analysis.flesch_reading_ease = 0.0;
+        analysis.flesch_kincaid_grade = 0.0;
+        analysis.gunning_fog = 0.0;
+        analysis.smog = 0.0;
+        analysis.coleman_liau = 0.0;
+        analysis.automated_readability = 0.0;
+        return;
+    }
+    let words_per_sentence = words / sentences;
+    let syllables_per_word = analysis.syllable_count as f64 / words;
+    let letters_per_word = analysis.letter_count as f64 / words;
+    let polysyllables = analysis.polysyllable_count as f64;
+
+    analysis.flesch_reading_ease = 206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word;
+    analysis.flesch_kincaid_grade = 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59;
+    analysis.gunning_fog = 0.4 * (words_per_sentence + 100.0 * polysyllables / words);
+    analysis.smog = 1.043 * (polysyllables * 30.0 / sentences).sqrt() + 3.1291;
+    analysis.coleman_liau =
+        0.0588 * letters_per_word * 100.0 - 0.296 * sentences / words * 100.0 - 15.8;
+    analysis.automated_readability = 4.71 * letters_per_word + 0.5 * words_per_sentence - 21.43;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_syllables() {
+        for (word, count) in [
+            ("cat", 1),
+            ("the", 1),
+            ("make", 1),
+            ("likes", 1),
+            ("jumped", 1),
+            ("table", 2),
+            ("wanted", 2),
+            ("boxes", 2),
+            ("AGREED", 2),
+            ("café", 2),
+            ("beautiful", 3),
+            ("readability", 5),
+            ("don't", 1),
+            ("123", 1),
+            ("", 1),
+        ] {
+            assert_eq!(syllables(word), count, "{}", word);
+        }
+    }
+
+    #[test]
+    fn computes_readability_indices() {
+        let mut analysis = Analysis {
+            all_word_count: 100,
+            sent_count: 5,
+            syllable_count: 150,
+            polysyllable_count: 10,
+            letter_count: 450,
+            ..Default::default()
+        };
+        update_readability(&mut analysis);
+        let assert_close = |value: f64, expected: f64| {
+            assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+        };
+        assert_close(analysis.flesch_reading_ease, 59.635);
+        assert_close(analysis.flesch_kincaid_grade, 9.91);
+        assert_close(analysis.gunning_fog, 12.0);
+        assert_close(analysis.smog, 11.20814326018867);
+        assert_close(analysis.coleman_liau, 9.18);
+        assert_close(analysis.automated_readability, 9.765);
+    }
+
+    #[test]
+    fn resets_readability_without_words_or_sentences() {
+        for (words, sentences) in [(0, 0), (0, 3), (10, 0)] {
+            let mut analysis = Analysis {
+                all_word_count: words,
+                sent_count: sentences,
+                syllable_count: 15,
+                letter_count: 40,
+                flesch_reading_ease: 50.0,
+                smog: 10.0,
+                ..Default::default()
+            };
+            update_readability(&mut analysis);
+            for index in [
+                analysis.flesch_reading_ease,
+                analysis.flesch_kincaid_grade,
+                analysis.gunning_fog,
+                analysis.smog,
+                analysis.coleman_liau,
+                analysis.automated_readability,
+            ] {
+                assert_eq!(index, 0.0);
+            }
+        }
+    }
+}
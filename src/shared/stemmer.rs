@@ -0,0 +1,149 @@
+use std::{borrow::Cow, collections::HashMap, fmt, path::Path};
+
+use rust_stemmers::Algorithm;
+
+const ALGORITHMS: &[(&str, Algorithm)] = &[
+    ("arabic", Algorithm::Arabic),
+    ("danish", Algorithm::Danish),
+    ("dutch", Algorithm::Dutch),
+    ("english", Algorithm::English),
+    ("finnish", Algorithm::Finnish),
+    ("french", Algorithm::French),
+    ("german", Algorithm::German),
+    ("greek", Algorithm::Greek),
+    ("hungarian", Algorithm::Hungarian),
+    ("italian", Algorithm::Italian),
+    ("norwegian", Algorithm::Norwegian),
+    ("portuguese", Algorithm::Portuguese),
+    ("romanian", Algorithm::Romanian),
+    ("russian", Algorithm::Russian),
+    ("spanish", Algorithm::Spanish),
+    ("swedish", Algorithm::Swedish),
+    ("tamil", Algorithm::Tamil),
+    ("turkish", Algorithm::Turkish),
+];
+
+enum Kind {
+    Snowball(&'static str, rust_stemmers::Stemmer),
+    Lemmas(HashMap<String, String>),
+}
+
+/// Reduces the inflected forms of words to the stem or lemma they are counted as.
+///
+/// Words are looked up lowercased, stems of the Snowball stemmers are always lowercase.
+pub struct Stemmer {
+    kind: Kind,
+}
+impl Stemmer {
+    /// Languages of the bundled Snowball stemmers.
+    pub const LANGUAGES: &'static [&'static str] = &[
+        "arabic",
+        "danish",
+        "dutch",
+        "english",
+        "finnish",
+        "french",
+        "german",
+        "greek",
+        "hungarian",
+        "italian",
+        "norwegian",
+        "portuguese",
+        "romanian",
+        "russian",
+        "spanish",
+        "swedish",
+        "tamil",
+        "turkish",
+    ];
+
+    /// Returns the Snowball stemmer of a language in [`Self::LANGUAGES`].
+    pub fn snowball(language: &str) -> Option<Self> {
+        ALGORITHMS.iter().find(|(name, _)| name.eq_ignore_ascii_case(language)).map(
+            |&(name, algorithm)| Self {
+                kind: Kind::Snowball(name, rust_stemmers::Stemmer::create(algorithm)),
+            },
+        )
+    }
+
+    /// Creates a lemmatizer from a table with a lemma followed by its forms on each line,
+    /// separated by whitespace. Words that are not in the table are counted unchanged.
+    ///
+    /// Lines starting with `#` are ignored.
+    pub fn parse_lemmas(table: &str) -> Self {
+        let mut lemmas = HashMap::new();
+        for line in table.lines().filter(|line| !line.trim_start().starts_with('#')) {
+            let mut words = line.split_whitespace();
+            let Some(lemma) = words.next() else {
+                continue;
+            };
+            for form in words {
+                lemmas.insert(form.to_lowercase(), lemma.to_owned());
+            }
+        }
+        Self {
+            kind: Kind::Lemmas(lemmas),
+        }
+    }
+
+    /// Reads a lemma table from a file in the format accepted by [`Self::parse_lemmas`].
+    pub fn lemmas_from_file(path: &Path) -> std::io::Result<Self> {
+        Ok(Self::parse_lemmas(&std::fs::read_to_string(path)?))
+    }
+
+    /// Returns the stem or lemma of a word.
+    pub fn stem<'a>(&'a self, word: &'a str) -> Cow<'a, str> {
+        let lowercase = match word.chars().any(char::is_uppercase) {
+            true => Cow::Owned(word.to_lowercase()),
+            false => Cow::Borrowed(word),
+        };
+        match (&self.kind, lowercase) {
+            (Kind::Snowball(_, stemmer), Cow::Borrowed(word)) => stemmer.stem(word),
+            (Kind::Snowball(_, stemmer), Cow::Owned(word)) => {
+                Cow::Owned(stemmer.stem(&word).into_owned())
+            }
+            (Kind::Lemmas(lemmas), lowercase) => {
+                lemmas.get(lowercase.as_ref()).map_or(Cow::Borrowed(word), Cow::from)
+            }
+        }
+    }
+}
+impl fmt::Debug for Stemmer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            Kind::Snowball(language, _) => f.debug_tuple("Snowball").field(language).finish(),
+            Kind::Lemmas(lemmas) => f.debug_tuple("Lemmas").field(&lemmas.len()).finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stems_lowercased_words() {
+        let stemmer = Stemmer::snowball("English").unwrap();
+        assert_eq!(stemmer.stem("running"), "run");
+        assert_eq!(stemmer.stem("Running"), "run");
+        assert_eq!(stemmer.stem("CONNECTIONS"), "connect");
+        assert!(Stemmer::snowball("klingon").is_none());
+        for language in Stemmer::LANGUAGES {
+            assert!(Stemmer::snowball(language).is_some(), "{}", language);
+        }
+    }
+
+    #[test]
+    fn looks_up_lemmas() {
+        let stemmer = Stemmer::parse_lemmas(
+            "# lemma forms\ngo goes went Gone\n  # be is was\n\nmouse mice\tMice\n",
+        );
+        assert_eq!(stemmer.stem("went"), "go");
+        assert_eq!(stemmer.stem("Went"), "go");
+        assert_eq!(stemmer.stem("gone"), "go");
+        assert_eq!(stemmer.stem("mice"), "mouse");
+        assert_eq!(stemmer.stem("Was"), "Was");
+        assert_eq!(stemmer.stem("#"), "#");
+        assert_eq!(stemmer.stem("Unknown"), "Unknown");
+    }
+}
@@ -0,0 +1,112 @@
+use std::{borrow::Cow, collections::HashSet, path::Path};
+
+const BUILTIN: &[(&str, &str)] = &[
+    ("de", include_str!("../../resources/stopwords/de.txt")),
+    ("en", include_str!("../../resources/stopwords/en.txt")),
+    ("es", include_str!("../../resources/stopwords/es.txt")),
+    ("fr", include_str!("../../resources/stopwords/fr.txt")),
+    ("it", include_str!("../../resources/stopwords/it.txt")),
+    ("nl", include_str!("../../resources/stopwords/nl.txt")),
+    ("pt", include_str!("../../resources/stopwords/pt.txt")),
+];
+
+/// Set of words that are excluded from the word frequencies.
+///
+/// Stopwords are matched case-insensitively, typographic apostrophes match ASCII apostrophes.
+#[derive(Default, Debug, Clone)]
+pub struct Stopwords {
+    words: HashSet<String>,
+}
+impl Stopwords {
+    /// Language codes of the bundled stopword lists.
+    pub const LANGUAGES: &'static [&'static str] = &["de", "en", "es", "fr", "it", "nl", "pt"];
+
+    /// Creates a set from a list of words separated by whitespace.
+    ///
+    /// Lines starting with `#` are ignored.
+    pub fn parse(list: &str) -> Self {
+        let mut stopwords = Self::default();
+        for line in list.lines().filter(|line| !line.trim_start().starts_with('#')) {
+            stopwords
+                .words
+                .extend(line.split_whitespace().map(|word| normalize(word).into_owned()));
+        }
+        stopwords
+    }
+
+    /// Returns the bundled stopword list for a language code in [`Self::LANGUAGES`].
+    pub fn builtin(language: &str) -> Option<Self> {
+        BUILTIN
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(language))
+            .map(|(_, list)| Self::parse(list))
+    }
+
+    /// Reads a stopword list from a file in the format accepted by [`Self::parse`].
+    pub fn from_file(path: &Path) -> std::io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Adds all words of another set.
+    pub fn extend(&mut self, other: Self) {
+        self.words.extend(other.words);
+    }
+
+    /// Returns `true` if the word is a stopword.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(normalize(word).as_ref())
+    }
+
+    /// Returns the number of stopwords.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns `true` if the set contains no stopwords.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+/// Returns the word as it is matched against the stopwords, lowercase and with typographic
+/// apostrophes replaced with ASCII apostrophes.
+pub(crate) fn normalize(word: &str) -> Cow<'_, str> {
+    match word.chars().any(|c| c.is_uppercase() || c == '\u{2019}') {
+        true => Cow::Owned(word.to_lowercase().replace('\u{2019}', "'")),
+        false => Cow::Borrowed(word),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lists() {
+        let stopwords =
+            Stopwords::parse("# Comment with words\nThe a\n  an\tDON\u{2019}T\n\n  # the\n");
+        assert_eq!(stopwords.len(), 4);
+        assert!(!stopwords.contains("comment"));
+        assert!(stopwords.contains("don't"));
+    }
+
+    #[test]
+    fn matches_case_insensitively() {
+        let stopwords = Stopwords::parse("the isn't");
+        for word in ["the", "The", "THE", "isn't", "Isn\u{2019}t", "ISN'T"] {
+            assert!(stopwords.contains(word), "{}", word);
+        }
+        assert!(!stopwords.contains("then"));
+        assert!(!stopwords.contains("isnt"));
+    }
+
+    #[test]
+    fn bundles_lists_of_all_languages() {
+        for language in Stopwords::LANGUAGES {
+            let stopwords = Stopwords::builtin(language).unwrap();
+            assert!(stopwords.len() > 100, "{}", language);
+        }
+        assert!(Stopwords::builtin("EN").unwrap().contains("The"));
+        assert!(Stopwords::builtin("xx").is_none());
+    }
+}
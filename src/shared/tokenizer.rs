@@ -0,0 +1,260 @@
+use std::{fmt::Debug, sync::Arc};
+
+use icu_segmenter::WordSegmenter;
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Splits text into the paragraphs, sentences and words that are counted.
+///
+/// Implementations are selected with [`Args::tokenizer`](super::Args::tokenizer), the
+/// [`UnicodeTokenizer`] is used if none is set.
+pub trait Tokenizer: Debug + Send + Sync {
+    /// Returns the words of the text.
+    fn words<'a>(&self, text: &'a str) -> Vec<&'a str>;
+
+    /// Returns the sentences of the text, its [UAX #29](https://unicode.org/reports/tr29/)
+    /// sentences by default.
+    ///
+    /// Sentences have to be slices of the text. Text that is counted in pieces is cut at the start
+    /// of a sentence, so the sentences of the text before it have to be the same.
+    fn sentences<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        text.unicode_sentences().collect()
+    }
+
+    /// Returns the paragraphs of the text, which are separated by two line breaks by default, with
+    /// `\r\n` counting as one.
+    ///
+    /// Paragraphs have to be separated by whitespace. Text that is counted in pieces is passed
+    /// together with the whitespace at the end of the previous piece, so that separators that
+    /// span two pieces are found.
+    fn paragraphs<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut paragraphs = Vec::new();
+        let mut start = 0;
+        // Index of a line break that is followed by a second one if it separates a paragraph
+        let mut line_break = None;
+        let mut bytes = text.bytes().enumerate().peekable();
+        while let Some((index, byte)) = bytes.next() {
+            let end = match byte {
+                b'\r' if bytes.next_if(|&(_, byte)| byte == b'\n').is_some() => index + 2,
+                b'\n' => index + 1,
+                _ => {
+                    line_break = None;
+                    continue;
+                }
+            };
+            match line_break.take() {
+                Some(line_break) => {
+                    paragraphs.push(&text[start..line_break]);
+                    start = end;
+                }
+                None => line_break = Some(index),
+            }
+        }
+        paragraphs.push(&text[start..]);
+        paragraphs
+    }
+}
+
+/// Returns whether a character is of a script that is written without spaces between words,
+/// Han, Hiragana, Katakana or Thai.
+fn is_unspaced(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0E7F}'         // Thai
+        | '\u{3005}' | '\u{3007}'       // Ideographic iteration mark and number zero
+        | '\u{3041}'..='\u{30FF}'       // Hiragana and Katakana
+        | '\u{31F0}'..='\u{31FF}'       // Katakana phonetic extensions
+        | '\u{3400}'..='\u{4DBF}'       // CJK unified ideographs extension A
+        | '\u{4E00}'..='\u{9FFF}'       // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}'       // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}'       // Halfwidth Katakana
+        | '\u{20000}'..='\u{3134F}'     // CJK unified ideographs extensions B to G
+    )
+}
+
+thread_local! {
+    /// Segmenter of Chinese, Japanese and Thai text with the dictionaries bundled by ICU4X, which
+    /// is not `Sync` and is created once per thread.
+    static SEGMENTER: WordSegmenter = WordSegmenter::new_dictionary();
+}
+
+/// Tokenizer of words and sentences as defined by [UAX #29](https://unicode.org/reports/tr29/).
+///
+/// Runs of Han, Hiragana, Katakana and Thai characters, which UAX #29 splits into single
+/// characters or leaves whole, are segmented into words with a bundled dictionary.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UnicodeTokenizer;
+impl UnicodeTokenizer {
+    fn segment<'a>(run: &'a str, words: &mut Vec<&'a str>) {
+        if run.is_empty() {
+            return;
+        }
+        let bounds = SEGMENTER.with(|segmenter| segmenter.segment_str(run).collect::<Vec<_>>());
+        for bound in bounds.windows(2) {
+            let word = &run[bound[0]..bound[1]];
+            if word.chars().any(char::is_alphanumeric) {
+                words.push(word);
+            }
+        }
+    }
+}
+impl Tokenizer for UnicodeTokenizer {
+    fn words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut words = Vec::new();
+        // Range of the run of adjacent words in scripts without spaces
+        let mut run = 0..0;
+        for (index, word) in text.unicode_word_indices() {
+            if !word.starts_with(is_unspaced) {
+                Self::segment(&text[std::mem::take(&mut run)], &mut words);
+                words.push(word);
+            } else if run.is_empty() || run.end != index {
+                Self::segment(
+                    &text[std::mem::replace(&mut run, index..index + word.len())],
+                    &mut words,
+                );
+            } else {
+                run.end = index + word.len();
+            }
+        }
+        Self::segment(&text[run], &mut words);
+        words
+    }
+}
+
+/// Tokenizer of words separated by whitespace, including their punctuation.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WhitespaceTokenizer;
+impl Tokenizer for WhitespaceTokenizer {
+    fn words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        text.split_whitespace().collect()
+    }
+}
+
+/// Tokenizer of words matching a regular expression.
+#[derive(Debug, Clone)]
+pub struct RegexTokenizer {
+    regex: Regex,
+}
+impl RegexTokenizer {
+    /// Returns a tokenizer of the non-empty matches of the pattern.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: Regex::new(pattern)?,
+        })
+    }
+}
+impl Tokenizer for RegexTokenizer {
+    fn words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        self.regex
+            .find_iter(text)
+            .map(|word| word.as_str())
+            .filter(|word| !word.is_empty())
+            .collect()
+    }
+}
+
+/// Tokenizer of the parts of identifiers in `snake_case`, `camelCase` and `PascalCase`, which
+/// splits words like `parseHttpResponse` and `HTTP_STATUS_CODE` into their parts.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IdentifierTokenizer;
+impl IdentifierTokenizer {
+    fn split<'a>(identifier: &'a str, words: &mut Vec<&'a str>) {
+        for part in identifier.split('_').filter(|part| !part.is_empty()) {
+            let chars = part.char_indices().collect::<Vec<_>>();
+            let mut start = 0;
+            for (position, &(index, c)) in chars.iter().enumerate().skip(1) {
+                let previous = chars[position - 1].1;
+                let next = chars.get(position + 1).map(|&(_, c)| c);
+                // Words start at an uppercase letter after a lowercase letter or digit, or at the
+                // last uppercase letter of an acronym that is followed by a lowercase letter
+                if c.is_uppercase()
+                    && (previous.is_lowercase()
+                        || previous.is_numeric()
+                        || (previous.is_uppercase() && next.is_some_and(char::is_lowercase)))
+                {
+                    words.push(&part[start..index]);
+                    start = index;
+                }
+            }
+            words.push(&part[start..]);
+        }
+    }
+}
+impl Tokenizer for IdentifierTokenizer {
+    fn words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut words = Vec::new();
+        for identifier in text.unicode_words() {
+            Self::split(identifier, &mut words);
+        }
+        words
+    }
+}
+
+/// Names of the tokenizers accepted by [`builtin_tokenizer`].
+pub const TOKENIZERS: &[&str] = &["unicode", "whitespace", "identifiers"];
+
+/// Returns the built-in tokenizer with a name in [`TOKENIZERS`].
+pub fn builtin_tokenizer(name: &str) -> Option<Arc<dyn Tokenizer>> {
+    match name.to_ascii_lowercase().as_str() {
+        "unicode" => Some(Arc::new(UnicodeTokenizer)),
+        "whitespace" => Some(Arc::new(WhitespaceTokenizer)),
+        "identifiers" => Some(Arc::new(IdentifierTokenizer)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_paragraphs_at_blank_lines() {
+        let tokenizer = WhitespaceTokenizer;
+        assert_eq!(tokenizer.paragraphs("One\ntwo\n\nThree\r\n\r\nFour"), [
+            "One\ntwo", "Three", "Four"
+        ]);
+        assert_eq!(tokenizer.paragraphs("One\n\r\nTwo\n\rThree\n\n\n\n"), [
+            "One",
+            "Two\n\rThree",
+            "",
+            ""
+        ]);
+        assert_eq!(tokenizer.paragraphs(""), [""]);
+    }
+
+    #[test]
+    fn splits_identifiers_into_parts() {
+        let tokenizer = IdentifierTokenizer;
+        assert_eq!(tokenizer.words("parseHttpResponse HTTP_STATUS_CODE"), [
+            "parse", "Http", "Response", "HTTP", "STATUS", "CODE"
+        ]);
+        assert_eq!(tokenizer.words("XMLHttpRequest __private_value utf8Decode v2Api"), [
+            "XML", "Http", "Request", "private", "value", "utf8", "Decode", "v2", "Api"
+        ]);
+        assert_eq!(tokenizer.words("Ünïcode_ÄÖÜ x"), ["Ünïcode", "ÄÖÜ", "x"]);
+    }
+
+    #[test]
+    fn skips_empty_regex_matches() {
+        let tokenizer = RegexTokenizer::new(r"[a-z]*").unwrap();
+        assert_eq!(tokenizer.words("one, two  3 three"), ["one", "two", "three"]);
+        assert!(RegexTokenizer::new("(").is_err());
+    }
+
+    #[test]
+    fn segments_runs_of_unspaced_scripts() {
+        let tokenizer = UnicodeTokenizer;
+        assert_eq!(tokenizer.words("我们今天去北京大学学习中文"), [
+            "我们", "今天", "去", "北京", "大学", "学习", "中文"
+        ]);
+        assert_eq!(tokenizer.words("ภาษาไทยง่ายนิดเดียว"), [
+            "ภาษา",
+            "ไทย",
+            "ง่าย",
+            "นิด",
+            "เดียว"
+        ]);
+        assert_eq!(tokenizer.words("我们用Rust写代码，version 2。"), [
+            "我们", "用", "Rust", "写", "代码", "version", "2"
+        ]);
+    }
+}
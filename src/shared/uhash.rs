@@ -7,6 +7,10 @@ use std::{
 
 use byteorder::{ByteOrder, NativeEndian};
 
+/// Hasher for keys that are hashes themselves, such as [`UniqueString`](super::UniqueString).
+///
+/// Writes of 8 bytes are used as the hash directly, other writes are hashed, so the hasher can be
+/// used with any key but only distributes keys that write a single well-distributed `u64`.
 #[derive(Default, Clone, Copy)]
 pub struct IdentityHasher {
     hash: u64,
@@ -17,7 +21,7 @@ impl Hasher for IdentityHasher {
         if bytes.len() == 8 {
             self.hash = self.hash.wrapping_add(NativeEndian::read_u64(bytes));
         } else {
-            unreachable!()
+            self.hash = folded_multiply(self.hash ^ ahash(bytes), 6364136223846793005u64);
         }
     }
 
@@ -65,3 +69,18 @@ pub const fn ahash(data: &[u8]) -> u64 {
     }
     (buffer.wrapping_mul(MULTIPLE) ^ PAD).rotate_left((buffer & 63) as u32)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_keys_of_any_size() {
+        let mut set = HashSet::<String, BuildHasherDefault<IdentityHasher>>::default();
+        set.insert("word".to_owned());
+        set.insert("other".to_owned());
+        assert!(set.contains("word"));
+        assert!(!set.contains("missing"));
+        assert_eq!(set.len(), 2);
+    }
+}
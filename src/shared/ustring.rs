@@ -7,7 +7,7 @@ use std::{
     hash::{Hash, Hasher},
     ops::Deref,
     ptr::NonNull,
-    sync::OnceLock,
+    sync::{Arc, OnceLock},
 };
 
 use bumpalo::Bump;
@@ -23,6 +23,13 @@ struct UniqueStringEntry {
     length: usize,
     hash:   u64,
     string: NonNull<u8>,
+    next:   Option<NonNull<u8>>,
+}
+impl UniqueStringEntry {
+    #[inline]
+    unsafe fn as_str(&self) -> &str {
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.string.as_ptr(), self.length))
+    }
 }
 
 struct UniqueStringBucket {
@@ -45,6 +52,8 @@ impl UniqueStringBucket {
         }
     }
 
+    /// Stores a string that is not stored yet, colliding entries are chained in front of the
+    /// existing entries with the same hash.
     fn store(&mut self, string: &str, hash: u64) -> NonNull<u8> {
         let str_addr = self.alloc.alloc_slice_copy(&[string.as_bytes(), &[0]].concat());
         let str_addr = unsafe { NonNull::new_unchecked(str_addr as *const _ as *mut _) };
@@ -52,15 +61,26 @@ impl UniqueStringBucket {
             length: string.len(),
             hash,
             string: str_addr,
+            next: self.store.get(&hash).copied(),
         });
         let ent_addr = unsafe { NonNull::new_unchecked(ent_addr as *const _ as *mut _) };
         self.store.insert(hash, ent_addr);
         ent_addr
     }
 
+    /// Returns the entry of a string, comparing the contents of all entries with the same hash.
     #[inline]
-    fn get(&self, hash: u64) -> Option<NonNull<u8>> {
-        self.store.get(&hash).copied()
+    fn get(&self, string: &str, hash: u64) -> Option<NonNull<u8>> {
+        let mut next = self.store.get(&hash).copied();
+        while let Some(ent_addr) = next {
+            let entry =
+                unsafe { (ent_addr.as_ptr() as *const UniqueStringEntry).as_ref().unwrap() };
+            if unsafe { entry.as_str() } == string {
+                return Some(ent_addr);
+            }
+            next = entry.next;
+        }
+        None
     }
 }
 
@@ -77,33 +97,115 @@ impl UniqueStringStore {
     #[inline]
     fn get_or_store(&self, string: &str, hash: u64) -> NonNull<u8> {
         let mut store = self.buckets[(hash % BUCKET_COUNT) as usize].lock();
-        store.get(hash).unwrap_or_else(|| store.store(string, hash))
+        store.get(string, hash).unwrap_or_else(|| store.store(string, hash))
+    }
+
+    fn allocated_bytes(&self) -> usize {
+        self.buckets.iter().map(|bucket| bucket.lock().alloc.allocated_bytes()).sum()
+    }
+}
+
+/// Arena that interned strings are stored in.
+///
+/// Clones share the same arena. Every [`UniqueString`] keeps the arena it was interned in alive,
+/// the memory of all strings is released when the last clone of the interner and the last of its
+/// strings are dropped.
+#[derive(Clone)]
+pub struct Interner {
+    store:      Arc<UniqueStringStore>,
+    /// Hash that all strings are interned with to force collisions in tests
+    #[cfg(test)]
+    fixed_hash: Option<u64>,
+}
+impl Interner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Self {
+            store:                   Arc::new(UniqueStringStore::new()),
+            #[cfg(test)]
+            fixed_hash:              None,
+        }
+    }
+
+    /// Returns an interner that interns all strings with the same hash.
+    #[cfg(test)]
+    pub(crate) fn colliding() -> Self {
+        Self {
+            fixed_hash: Some(0),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the process-wide interner used by [`UniqueString::new`], which is never dropped.
+    pub fn global() -> &'static Self {
+        INTERNED_STRINGS.get_or_init(Self::new)
+    }
+
+    /// Returns the interned string equal to the string, storing it if it's not interned yet.
+    ///
+    /// ```
+    /// use wordstat::Interner;
+    ///
+    /// let interner = Interner::new();
+    /// let word = interner.intern("word");
+    /// assert_eq!(word, interner.intern("word"));
+    /// assert_eq!(word.as_str(), "word");
+    /// assert!(interner.allocated_bytes() > 0);
+    /// ```
+    #[inline]
+    pub fn intern(&self, string: &str) -> UniqueString {
+        #[cfg(test)]
+        if let Some(hash) = self.fixed_hash {
+            return UniqueString::create_with_hash(string, hash).intern_in(self);
+        }
+        UniqueString::create(string).intern_in(self)
+    }
+
+    /// Returns the number of bytes allocated for the stored strings.
+    pub fn allocated_bytes(&self) -> usize {
+        self.store.allocated_bytes()
+    }
+}
+impl Default for Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Debug for Interner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Interner")
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish_non_exhaustive()
     }
 }
 
-static INTERNED_STRINGS: OnceLock<UniqueStringStore> = OnceLock::new();
+static INTERNED_STRINGS: OnceLock<Interner> = OnceLock::new();
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+/// Interned string that is compared by its address and keeps the arena of its [`Interner`] alive.
+#[derive(Clone)]
 pub struct UniqueString {
     entry: NonNull<u8>,
+    store: Arc<UniqueStringStore>,
 }
 unsafe impl Send for UniqueString {}
 unsafe impl Sync for UniqueString {}
 
+/// String with its hash that is about to be interned.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct UniqueStringIntermediary<'a> {
+pub(crate) struct UniqueStringIntermediary<'a> {
     hash:   u64,
     string: &'a str,
 }
 
 impl UniqueString {
+    /// Interns a string in the [global interner](Interner::global).
     #[inline]
     pub fn new(string: &str) -> Self {
         Self::create(string).intern()
     }
 
     #[inline]
-    pub const fn create(string: &str) -> UniqueStringIntermediary<'_> {
+    pub(crate) const fn create(string: &str) -> UniqueStringIntermediary<'_> {
         UniqueStringIntermediary {
             hash: ahash(string.as_bytes()),
             string,
@@ -115,22 +217,24 @@ impl UniqueString {
         Self::create_with_hash(string, hash).intern()
     }
 
+    /// Creates an intermediary with a precomputed hash instead of the hash of the string.
+    ///
+    /// Interning the same string with different hashes creates separate entries, so this is only
+    /// used to force hash collisions in tests.
     #[inline]
-    const fn create_with_hash(string: &str, hash: u64) -> UniqueStringIntermediary<'_> {
+    pub(crate) const fn create_with_hash(string: &str, hash: u64) -> UniqueStringIntermediary<'_> {
         UniqueStringIntermediary { hash, string }
     }
 
+    /// Returns the string.
     #[inline]
-    pub fn as_str(&self) -> &'static str {
-        unsafe {
-            let entry = (self.entry.as_ptr() as *const UniqueStringEntry).as_ref().unwrap();
-            let slice = std::slice::from_raw_parts(entry.string.as_ptr(), entry.length);
-            std::str::from_utf8_unchecked(slice)
-        }
+    pub fn as_str(&self) -> &str {
+        unsafe { (self.entry.as_ptr() as *const UniqueStringEntry).as_ref().unwrap().as_str() }
     }
 
+    /// Returns the string as a C string, strings are stored with a terminating NUL byte.
     #[inline]
-    pub fn as_cstr(&self) -> &'static CStr {
+    pub fn as_cstr(&self) -> &CStr {
         unsafe {
             let entry = (self.entry.as_ptr() as *const UniqueStringEntry).as_ref().unwrap();
             std::ffi::CStr::from_bytes_with_nul_unchecked(std::slice::from_raw_parts(
@@ -140,41 +244,37 @@ impl UniqueString {
         }
     }
 
+    /// Returns the string as a borrowed [`Cow`].
     #[inline]
-    pub fn as_cow(&self) -> Cow<'static, str> {
+    pub fn as_cow(&self) -> Cow<'_, str> {
         Cow::Borrowed(self.as_str())
     }
 
+    /// Returns the hash the string was interned with, which is used as its [`Hash`].
     #[inline]
     pub fn hash(&self) -> u64 {
         unsafe { (self.entry.as_ptr() as *const UniqueStringEntry).as_ref().unwrap().hash }
     }
 
     #[inline]
-    pub fn entry(&self) -> NonNull<u8> {
+    pub(crate) fn entry(&self) -> NonNull<u8> {
         self.entry
     }
 }
 impl UniqueStringIntermediary<'_> {
+    /// Interns the string in the [global interner](Interner::global).
     #[inline]
-    pub fn intern(self) -> UniqueString {
-        UniqueString {
-            entry: INTERNED_STRINGS
-                .get_or_init(UniqueStringStore::new)
-                .get_or_store(self.string, self.hash),
-        }
+    pub(crate) fn intern(self) -> UniqueString {
+        self.intern_in(Interner::global())
     }
-}
-impl From<UniqueStringIntermediary<'_>> for UniqueString {
-    #[inline]
-    fn from(intermediary: UniqueStringIntermediary<'_>) -> Self {
-        intermediary.intern()
-    }
-}
-impl From<UniqueString> for &str {
+
+    /// Interns the string in the given interner.
     #[inline]
-    fn from(string: UniqueString) -> Self {
-        string.as_str()
+    pub(crate) fn intern_in(self, interner: &Interner) -> UniqueString {
+        UniqueString {
+            entry: interner.store.get_or_store(self.string, self.hash),
+            store: interner.store.clone(),
+        }
     }
 }
 impl From<&str> for UniqueString {
@@ -201,22 +301,28 @@ impl From<&String> for UniqueString {
         Self::new(string.as_str())
     }
 }
-impl From<UniqueString> for &CStr {
+impl<'a> From<&'a UniqueString> for &'a str {
     #[inline]
-    fn from(string: UniqueString) -> Self {
+    fn from(string: &'a UniqueString) -> Self {
+        string.as_str()
+    }
+}
+impl<'a> From<&'a UniqueString> for &'a CStr {
+    #[inline]
+    fn from(string: &'a UniqueString) -> Self {
         string.as_cstr()
     }
 }
-impl From<UniqueString> for CString {
+impl<'a> From<&'a UniqueString> for Cow<'a, str> {
     #[inline]
-    fn from(string: UniqueString) -> Self {
-        string.as_cstr().into()
+    fn from(string: &'a UniqueString) -> Self {
+        string.as_cow()
     }
 }
-impl From<UniqueString> for Cow<'_, str> {
+impl From<UniqueString> for CString {
     #[inline]
     fn from(string: UniqueString) -> Self {
-        string.as_cow()
+        string.as_cstr().into()
     }
 }
 impl Deref for UniqueString {
@@ -240,7 +346,13 @@ impl AsRef<CStr> for UniqueString {
     }
 }
 
-#[allow(clippy::derived_hash_with_manual_eq)]
+impl PartialEq for UniqueString {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.entry == other.entry
+    }
+}
+impl Eq for UniqueString {}
 impl Hash for UniqueString {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -274,3 +386,45 @@ impl Debug for UniqueString {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interns_equal_strings_once() {
+        let interner = Interner::new();
+        let word = interner.intern("word");
+        assert_eq!(word, interner.intern("word"));
+        assert_ne!(word, interner.intern("other"));
+        assert_eq!(word.as_str(), "word");
+    }
+
+    #[test]
+    fn outlives_interner() {
+        let word = Interner::new().intern("word");
+        assert_eq!(word.as_str(), "word");
+
+        let interner = Interner::new();
+        let words = vec![interner.intern("first"), interner.intern("second")];
+        let cloned = words.clone();
+        drop(words);
+        drop(interner);
+        assert_eq!(cloned.iter().map(UniqueString::as_str).collect::<Vec<_>>(), [
+            "first", "second"
+        ]);
+    }
+
+    #[test]
+    fn separates_colliding_strings() {
+        let interner = Interner::new();
+        let first = UniqueString::create_with_hash("first", 0).intern_in(&interner);
+        let second = UniqueString::create_with_hash("second", 0).intern_in(&interner);
+        assert_ne!(first, second);
+        assert_eq!(first.hash(), second.hash());
+        assert_eq!(first.as_str(), "first");
+        assert_eq!(second.as_str(), "second");
+        assert_eq!(UniqueString::create_with_hash("first", 0).intern_in(&interner), first);
+        assert_eq!(UniqueString::create_with_hash("second", 0).intern_in(&interner), second);
+    }
+}
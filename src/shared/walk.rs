@@ -0,0 +1,273 @@
+use std::path::{Component, Path};
+
+use ignore::{
+    overrides::{Override, OverrideBuilder},
+    DirEntry, Error, WalkBuilder,
+};
+
+use super::{archive::is_archive, compress::uncompressed_path, Args};
+
+/// Returns whether the extension of a file, or of a compressed file without the extension of its
+/// compression format, is one of the extensions.
+pub fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    let matches = |path: &Path| {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                extensions
+                    .iter()
+                    .any(|filter| filter.trim_start_matches('.').eq_ignore_ascii_case(extension))
+            })
+    };
+    extensions.is_empty() || matches(path) || matches(&uncompressed_path(path))
+}
+
+/// Returns the matcher of glob patterns of files to include and to exclude for paths relative to
+/// the root.
+fn overrides(root: &Path, include: &[String], exclude: &[String]) -> Result<Override, Error> {
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in include {
+        overrides.add(glob)?;
+    }
+    for glob in exclude {
+        overrides.add(&format!("!{}", glob))?;
+    }
+    overrides.build()
+}
+
+/// Returns the files to analyze in a path, which is either a file or a directory.
+///
+/// Files in directories are filtered by [`Args::include`], [`Args::exclude`] and
+/// [`Args::extensions`], and by ignore files and their visibility unless [`Args::no_ignore`] and
+/// [`Args::hidden`] are set. Archives are returned unless they are excluded or ignored, their
+/// files are filtered by [`member_filter`]. A path to a file is always returned.
+pub fn walk<'a>(
+    path: &Path, args: &'a Args,
+) -> Result<impl Iterator<Item = Result<DirEntry, Error>> + 'a, Error> {
+    // Included files are matched after walking, as the walk yields files matching an override
+    // even if they are hidden or ignored
+    let include = overrides(path, &args.include, &[])?;
+    let exclude = overrides(path, &[], &args.exclude)?;
+    let respect_ignore = !args.no_ignore;
+    let walk = WalkBuilder::new(path)
+        .follow_links(args.follow_symlinks)
+        .max_depth(if args.recursive { None } else { Some(1) })
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .hidden(!args.hidden)
+        .parents(respect_ignore)
+        .ignore(respect_ignore)
+        .git_ignore(respect_ignore)
+        .git_global(respect_ignore)
+        .git_exclude(respect_ignore)
+        .require_git(false)
+        .overrides(exclude)
+        .filter_entry(move |entry| !respect_ignore || entry.file_name() != ".git")
+        .build();
+
+    Ok(walk.filter(move |entry| match entry {
+        Ok(entry) => {
+            entry.file_type().is_some_and(|file_type| file_type.is_file())
+                && (entry.depth() == 0
+                    || is_archive(entry.path())
+                    || (!include.matched(entry.path(), false).is_ignore()
+                        && has_extension(entry.path(), &args.extensions)))
+        }
+        Err(_) => true,
+    }))
+}
+
+/// Returns a filter of the paths of the files in an archive, which filters them like the files in
+/// a directory by [`Args::include`], [`Args::exclude`], [`Args::extensions`] and their visibility
+/// unless [`Args::hidden`] is set. Ignore files in archives are not read.
+pub fn member_filter(args: &Args) -> Result<impl Fn(&Path) -> bool + '_, Error> {
+    let overrides = overrides(Path::new(""), &args.include, &args.exclude)?;
+    Ok(move |path: &Path| {
+        let is_hidden = |component: Component| matches!(component, Component::Normal(name) if name.to_string_lossy().starts_with('.'));
+        // Excluding a directory excludes the files in it, as when walking a directory
+        let is_excluded = path
+            .ancestors()
+            .skip(1)
+            .take_while(|parent| !parent.as_os_str().is_empty())
+            .any(|parent| overrides.matched(parent, true).is_ignore());
+        (args.hidden || !path.components().any(is_hidden))
+            && !is_excluded
+            && !overrides.matched(path, false).is_ignore()
+            && has_extension(path, &args.extensions)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(args: &Args) -> Vec<&'static str> {
+        let filter = member_filter(args).unwrap();
+        [
+            "chapter1.txt",
+            "book/chapter2.md",
+            "book/drafts/chapter3.txt",
+            "book/notes.txt.gz",
+            ".hidden.txt",
+            "book/.cache/chapter4.txt",
+        ]
+        .into_iter()
+        .filter(|path| filter(Path::new(path)))
+        .collect()
+    }
+
+    /// Creates a directory with the files, removed when the returned guard is dropped.
+    struct TempDir(std::path::PathBuf);
+    impl TempDir {
+        fn new(name: &str, files: &[&str]) -> Self {
+            let root =
+                std::env::temp_dir().join(format!("wordstat-{}-{}", name, std::process::id()));
+            for file in files {
+                let path = root.join(file);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, "content").unwrap();
+            }
+            Self(root)
+        }
+    }
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn walked(path: &Path, args: &Args) -> Vec<String> {
+        let mut files = walk(path, args)
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                let file = entry.path().strip_prefix(path).unwrap_or(entry.path());
+                file.to_string_lossy().replace('\\', "/")
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn filters_files_in_directories() {
+        let directory = TempDir::new("walk", &[
+            "a.txt",
+            "b.md",
+            "ignored.txt",
+            "skipped.log",
+            ".hidden.txt",
+            ".git/config",
+            "sub/c.txt",
+            "sub/deep/d.txt",
+        ]);
+        let root = directory.0.as_path();
+        std::fs::write(root.join(".gitignore"), "ignored.txt\n").unwrap();
+        std::fs::write(root.join(".ignore"), "*.log\n").unwrap();
+        let args = |args: Args| Args {
+            recursive: true,
+            ..args
+        };
+
+        assert_eq!(walked(root, &args(Args::default())), [
+            "a.txt",
+            "b.md",
+            "sub/c.txt",
+            "sub/deep/d.txt"
+        ]);
+        let flat = Args::default();
+        assert_eq!(walked(root, &flat), ["a.txt", "b.md"]);
+        let hidden = args(Args {
+            hidden: true,
+            ..Default::default()
+        });
+        assert_eq!(walked(root, &hidden), [
+            ".gitignore",
+            ".hidden.txt",
+            ".ignore",
+            "a.txt",
+            "b.md",
+            "sub/c.txt",
+            "sub/deep/d.txt"
+        ]);
+        let no_ignore = args(Args {
+            no_ignore: true,
+            ..Default::default()
+        });
+        assert_eq!(walked(root, &no_ignore), [
+            "a.txt",
+            "b.md",
+            "ignored.txt",
+            "skipped.log",
+            "sub/c.txt",
+            "sub/deep/d.txt"
+        ]);
+        let include = args(Args {
+            include: vec!["*.txt".into()],
+            exclude: vec!["deep".into()],
+            ..Default::default()
+        });
+        assert_eq!(walked(root, &include), ["a.txt", "sub/c.txt"]);
+        let include = args(Args {
+            include: vec!["sub/*.txt".into(), "*.log".into()],
+            ..Default::default()
+        });
+        assert_eq!(walked(root, &include), ["sub/c.txt"]);
+        let extensions = args(Args {
+            extensions: vec![".md".into()],
+            ..Default::default()
+        });
+        assert_eq!(walked(root, &extensions), ["b.md"]);
+
+        // Paths to files are returned regardless of the filters
+        assert_eq!(walked(&root.join("ignored.txt"), &extensions), [""]);
+    }
+
+    #[test]
+    fn walks_archives_regardless_of_include() {
+        let directory = TempDir::new("walk-archives", &[
+            "a.txt",
+            "b.md",
+            "book.zip",
+            "notes.tar.gz",
+            "ignored.zip",
+            "excluded.tgz",
+        ]);
+        let root = directory.0.as_path();
+        std::fs::write(root.join(".ignore"), "ignored.zip\n").unwrap();
+        let args = Args {
+            include: vec!["*.txt".into()],
+            exclude: vec!["*.tgz".into()],
+            extensions: vec!["txt".into()],
+            ..Default::default()
+        };
+        assert_eq!(walked(root, &args), ["a.txt", "book.zip", "notes.tar.gz"]);
+    }
+
+    #[test]
+    fn filters_archive_members() {
+        assert_eq!(members(&Args::default()), [
+            "chapter1.txt",
+            "book/chapter2.md",
+            "book/drafts/chapter3.txt",
+            "book/notes.txt.gz"
+        ]);
+        let hidden = Args {
+            hidden: true,
+            ..Default::default()
+        };
+        assert_eq!(members(&hidden).len(), 6);
+
+        let include = Args {
+            include: vec!["*.txt".into(), "book/*.md".into()],
+            exclude: vec!["drafts".into()],
+            ..Default::default()
+        };
+        assert_eq!(members(&include), ["chapter1.txt", "book/chapter2.md"]);
+        let extensions = Args {
+            exclude: vec!["/chapter1.txt".into()],
+            extensions: vec!["txt".into()],
+            ..Default::default()
+        };
+        assert_eq!(members(&extensions), ["book/drafts/chapter3.txt", "book/notes.txt.gz"]);
+    }
+}